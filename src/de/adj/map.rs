@@ -12,8 +12,8 @@
 use crate::de::seed::SeedFactory;
 use crate::util::de::content::{Content, ContentDeserializer};
 
-use std;
-use std::marker::PhantomData;
+use core;
+use core::marker::PhantomData;
 
 use serde;
 
@@ -47,7 +47,7 @@ where
     D: serde::Deserializer<'de>,
     F: SeedFactory<'de, T>,
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'k Kc>,
+    K: core::cmp::PartialEq<&'k Kc>,
 {
     deserialize_seed::<K, _, _, _, _>(
         deserializer,
@@ -84,7 +84,7 @@ where
     F: SeedFactory<'de, S::Value>,
     S: serde::de::DeserializeSeed<'de>,
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'k Kc>,
+    K: core::cmp::PartialEq<&'k Kc>,
 {
     deserializer.deserialize_map(Visitor::<K, _, _, _>::new(
         tag_key,
@@ -140,13 +140,13 @@ impl<'a, K, Kc: ?Sized, F, S> Visitor<'a, K, Kc, F, S> {
 impl<'de, 'a, K, Kc: ?Sized, F, S> serde::de::Visitor<'de> for Visitor<'a, K, Kc, F, S>
 where
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'a Kc>,
+    K: core::cmp::PartialEq<&'a Kc>,
     F: SeedFactory<'de, S::Value>,
     S: serde::de::DeserializeSeed<'de>,
 {
     type Value = F::Value;
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a map with exactly two entries")
     }
 
@@ -230,7 +230,7 @@ where
     V: serde::Deserialize<'de>,
     D: serde::Deserializer<'de>,
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'a Kc>,
+    K: core::cmp::PartialEq<&'a Kc>,
 {
     deserialize_known_seed::<K, _, _, _, _>(
         deserializer,
@@ -268,7 +268,7 @@ where
     V: serde::de::DeserializeSeed<'de>,
     D: serde::Deserializer<'de>,
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'a Kc>,
+    K: core::cmp::PartialEq<&'a Kc>,
 {
     deserializer.deserialize_map(KnownVisitor::<K, _, _, _>::new(
         tag_seed, value_seed, tag_key, value_key,
@@ -319,11 +319,11 @@ where
     T: serde::de::DeserializeSeed<'de>,
     V: serde::de::DeserializeSeed<'de>,
     K: serde::Deserialize<'de>,
-    K: std::cmp::PartialEq<&'a Kc>,
+    K: core::cmp::PartialEq<&'a Kc>,
 {
     type Value = (T::Value, V::Value);
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a map with exactly two entries")
     }
 
@@ -382,7 +382,7 @@ enum Key {
 struct KeySeed<'a, Kc: ?Sized + 'a, Kd> {
     tag_key:     &'a Kc,
     value_key:   &'a Kc,
-    _phantom_kd: std::marker::PhantomData<Kd>,
+    _phantom_kd: core::marker::PhantomData<Kd>,
 }
 
 impl<'a, Kc: ?Sized, Kd> KeySeed<'a, Kc, Kd> {
@@ -390,7 +390,7 @@ impl<'a, Kc: ?Sized, Kd> KeySeed<'a, Kc, Kd> {
         KeySeed {
             tag_key:     tag_key,
             value_key:   value_key,
-            _phantom_kd: std::marker::PhantomData,
+            _phantom_kd: core::marker::PhantomData,
         }
     }
 }
@@ -398,7 +398,7 @@ impl<'a, Kc: ?Sized, Kd> KeySeed<'a, Kc, Kd> {
 impl<'de, 'a, Kc: ?Sized, Kd> serde::de::DeserializeSeed<'de> for KeySeed<'a, Kc, Kd>
 where
     Kd: serde::de::Deserialize<'de>,
-    Kd: std::cmp::PartialEq<&'a Kc>,
+    Kd: core::cmp::PartialEq<&'a Kc>,
 {
     type Value = Key;
 
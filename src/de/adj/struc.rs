@@ -12,8 +12,8 @@
 use crate::de::seed::SeedFactory;
 use crate::util::de::content::{Content, ContentDeserializer};
 
-use std;
-use std::marker::PhantomData;
+use core;
+use core::marker::PhantomData;
 
 use serde;
 
@@ -152,7 +152,7 @@ where
 {
     type Value = F::Value;
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a struct with exactly two fields")
     }
 
@@ -345,7 +345,7 @@ where
 {
     type Value = (T::Value, V::Value);
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a struct with exactly two fields")
     }
 
@@ -440,7 +440,7 @@ impl<'de> serde::de::DeserializeSeed<'de> for KeySeed {
 impl<'de> serde::de::Visitor<'de> for KeySeed {
     type Value = Key;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
         formatter.write_str("either the specified tag- or value-key")
     }
 
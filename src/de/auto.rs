@@ -0,0 +1,251 @@
+//! Deserialization of tagged values in a tagging format detected from the
+//! data.
+//!
+//! Data written by different producers may use different tagging formats.
+//! The functions in this module buffer the tagged value once, detect the
+//! tagging format from the shape of the buffered value, and then deserialize
+//! it accordingly. The candidate formats are given as [`TagFormat`]s and
+//! tried in order, the first matching one is used. A value matches a format
+//! if it is
+//!
+//! - a map with a single entry, for [`External`](TagFormat::External),
+//! - a map containing the tag key, for [`Internal`](TagFormat::Internal),
+//! - a sequence with two elements, for [`AdjTuple`](TagFormat::AdjTuple),
+//! - a map with exactly the tag and value keys, for
+//!   [`AdjMap`](TagFormat::AdjMap) and [`AdjStruct`](TagFormat::AdjStruct).
+//!
+//! As these shapes overlap, more specific formats should be listed first,
+//! e.g. adjacent tagging using maps before internal tagging before external
+//! tagging. If no format matches, the returned error describes why each
+//! candidate has been ruled out.
+//!
+//! # Warning
+//!
+//! Detection requires a self-describing data format.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::WithTag;
+//! use serde_tagged::format::TagFormat;
+//!
+//! const FORMATS: &[TagFormat] = &[
+//!     TagFormat::adj_map("type", "value"),
+//!     TagFormat::internal("type"),
+//!     TagFormat::AdjTuple,
+//!     TagFormat::External,
+//! ];
+//!
+//! # fn main() {
+//! let documents = [
+//!     r#"{ "type": "foo", "value": { "bar": 42 } }"#,
+//!     r#"{ "type": "foo", "bar": 42 }"#,
+//!     r#"[ "foo", { "bar": 42 } ]"#,
+//!     r#"{ "foo": { "bar": 42 } }"#,
+//! ];
+//!
+//! for json in &documents {
+//!     let mut de = serde_json::Deserializer::from_str(json);
+//!     let (tag, value): (String, serde_json::Value) =
+//!         serde_tagged::de::auto::deserialize(&mut de, FORMATS, WithTag::new()).unwrap();
+//!
+//!     assert_eq!(tag, "foo");
+//!     assert_eq!(value, serde_json::json!({ "bar": 42 }));
+//! }
+//! # }
+//! ```
+
+use crate::de::seed::SeedFactory;
+use crate::format::TagFormat;
+use crate::util::de::content::{Content, ContentDeserializer};
+
+use core::fmt::{self, Write};
+use core::marker::PhantomData;
+
+use alloc::string::String;
+
+use serde;
+
+
+/// Deserialize a tagged value in the first of the given tagging formats
+/// matching the shape of the value.
+///
+/// The deserializer controls the underlying data format while the seed-factory
+/// specifies the instructions (depending on the tag) on how the value should be
+/// deserialized. See the [module documentation](crate::de::auto) for details
+/// on the detection.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub fn deserialize<'de, T, D, F>(
+    deserializer: D,
+    formats: &[TagFormat],
+    seed_factory: F,
+) -> Result<F::Value, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, T>,
+{
+    deserialize_seed(deserializer, formats, seed_factory, PhantomData::<T>)
+}
+
+
+/// Deserialize a tagged value in the first of the given tagging formats
+/// matching the shape of the value, using the given `DeserializeSeed` for the
+/// tag.
+///
+/// See [`deserialize`] for more information.
+pub fn deserialize_seed<'de, D, F, S>(
+    deserializer: D,
+    formats: &[TagFormat],
+    seed_factory: F,
+    tag_seed: S,
+) -> Result<F::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, S::Value>,
+    S: serde::de::DeserializeSeed<'de>,
+{
+    use serde::de::{Deserialize, Error};
+
+    let content = Content::deserialize(deserializer)?;
+    let format = detect(&content, formats).map_err(D::Error::custom)?;
+
+    let deserializer = ContentDeserializer::<D::Error>::new(content);
+    crate::de::dynamic::deserialize_seed(deserializer, format, seed_factory, tag_seed)
+}
+
+
+/// Returns the first format matching the given value, or a description of
+/// why each format has been ruled out.
+fn detect<'f>(content: &Content, formats: &'f [TagFormat]) -> Result<&'f TagFormat, String> {
+    if formats.is_empty() {
+        return Err(String::from(
+            "cannot detect tagging format: no candidate formats",
+        ));
+    }
+
+    let mut msg = String::from("cannot detect tagging format");
+
+    for format in formats {
+        match check(content, format) {
+            Ok(()) => return Ok(format),
+            Err(reason) => {
+                let _ = write!(msg, "; {}: {}", Name(format), reason);
+            },
+        }
+    }
+
+    Err(msg)
+}
+
+/// Checks whether the given value matches the shape of the given format.
+fn check(content: &Content, format: &TagFormat) -> Result<(), String> {
+    let mut reason = String::new();
+
+    let _ = match (format, content) {
+        (TagFormat::External, Content::Map(entries)) if entries.len() == 1 => return Ok(()),
+        (TagFormat::External, _) => write!(
+            reason,
+            "expected a map with a single entry, found {}",
+            Found(content)
+        ),
+
+        (TagFormat::Internal { tag_key }, Content::Map(entries)) => {
+            if entries.iter().any(|(k, _)| key(k) == Some(&**tag_key)) {
+                return Ok(());
+            }
+            write!(reason, "map does not contain tag key `{}`", tag_key)
+        },
+        (TagFormat::Internal { .. }, _) => {
+            write!(reason, "expected a map, found {}", Found(content))
+        },
+
+        (TagFormat::AdjTuple, Content::Seq(elements)) if elements.len() == 2 => return Ok(()),
+        (TagFormat::AdjTuple, _) => write!(
+            reason,
+            "expected a sequence with two elements, found {}",
+            Found(content)
+        ),
+
+        (TagFormat::AdjMap { tag_key, value_key }, _)
+        | (
+            TagFormat::AdjStruct {
+                tag_key, value_key, ..
+            },
+            _,
+        ) => match *content {
+            Content::Map(ref entries) if entries.len() == 2 => {
+                let has = |k: &str| entries.iter().any(|(e, _)| key(e) == Some(k));
+
+                if has(tag_key) && has(value_key) {
+                    return Ok(());
+                }
+                write!(
+                    reason,
+                    "map does not contain keys `{}` and `{}`",
+                    tag_key, value_key
+                )
+            },
+            _ => write!(
+                reason,
+                "expected a map with keys `{}` and `{}`, found {}",
+                tag_key,
+                value_key,
+                Found(content)
+            ),
+        },
+    };
+
+    Err(reason)
+}
+
+/// Returns the given map key as string, if it is one.
+fn key<'a>(content: &'a Content) -> Option<&'a str> {
+    match *content {
+        Content::Str(s) => Some(s),
+        Content::String(ref s) => Some(s),
+        Content::Bytes(b) => core::str::from_utf8(b).ok(),
+        Content::ByteBuf(ref b) => core::str::from_utf8(b).ok(),
+        _ => None,
+    }
+}
+
+
+/// Displays the name of a format, as used in [`ser`](crate::ser).
+struct Name<'a>(&'a TagFormat);
+
+impl<'a> fmt::Display for Name<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self.0 {
+            TagFormat::External => "external",
+            TagFormat::Internal { .. } => "internal",
+            TagFormat::AdjTuple => "adj::tuple",
+            TagFormat::AdjMap { .. } => "adj::map",
+            TagFormat::AdjStruct { .. } => "adj::struc",
+        })
+    }
+}
+
+/// Displays a short description of a buffered value.
+struct Found<'a, 'de>(&'a Content<'de>);
+
+impl<'a, 'de> fmt::Display for Found<'a, 'de> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            Content::Map(ref entries) if entries.len() == 1 => write!(f, "a map with 1 entry"),
+            Content::Map(ref entries) => write!(f, "a map with {} entries", entries.len()),
+            Content::Seq(ref elements) if elements.len() == 1 => {
+                write!(f, "a sequence with 1 element")
+            },
+            Content::Seq(ref elements) => {
+                write!(f, "a sequence with {} elements", elements.len())
+            },
+            ref other => write!(f, "{}", other.unexpected()),
+        }
+    }
+}
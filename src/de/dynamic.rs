@@ -0,0 +1,113 @@
+//! Deserialization of tagged values in a tagging format selected at runtime.
+//!
+//! See [`ser::dynamic`](crate::ser::dynamic) for more information.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::WithTag;
+//! use serde_tagged::format::TagFormat;
+//!
+//! # fn main() {
+//! let format = TagFormat::internal("type");
+//! let json = r#"{ "type": "foo", "bar": 42 }"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let (tag, value): (String, serde_json::Value) =
+//!     serde_tagged::de::dynamic::deserialize(&mut de, &format, WithTag::new()).unwrap();
+//!
+//! assert_eq!(tag, "foo");
+//! assert_eq!(value, serde_json::json!({ "bar": 42 }));
+//! # }
+//! ```
+
+use crate::de::seed::SeedFactory;
+use crate::format::{TagFormat, OWNED_ADJ_STRUCT};
+
+use core::marker::PhantomData;
+
+use alloc::string::String;
+
+use serde;
+
+
+/// Deserialize a tagged value in the given tagging format.
+///
+/// The deserializer controls the underlying data format while the seed-factory
+/// specifies the instructions (depending on the tag) on how the value should be
+/// deserialized.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub fn deserialize<'de, T, D, F>(
+    deserializer: D,
+    format: &TagFormat,
+    seed_factory: F,
+) -> Result<F::Value, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, T>,
+{
+    deserialize_seed(deserializer, format, seed_factory, PhantomData::<T>)
+}
+
+
+/// Deserialize a tagged value in the given tagging format, using the given
+/// `DeserializeSeed` for the tag.
+///
+/// See [`deserialize`] for more information.
+///
+/// The name and keys of an [`AdjStruct`](TagFormat::AdjStruct) format must be
+/// borrowed, see [`TagFormat`] for details.
+pub fn deserialize_seed<'de, D, F, S>(
+    deserializer: D,
+    format: &TagFormat,
+    seed_factory: F,
+    tag_seed: S,
+) -> Result<F::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, S::Value>,
+    S: serde::de::DeserializeSeed<'de>,
+{
+    use alloc::borrow::Cow;
+
+    match *format {
+        TagFormat::External => {
+            crate::de::external::deserialize_seed(deserializer, seed_factory, tag_seed)
+        },
+        TagFormat::Internal { ref tag_key } => deserializer.deserialize_any(
+            crate::de::internal::Visitor::with_key(tag_key, seed_factory, tag_seed),
+        ),
+        TagFormat::AdjTuple => {
+            crate::de::adj::tuple::deserialize_seed(deserializer, seed_factory, tag_seed)
+        },
+        TagFormat::AdjMap {
+            ref tag_key,
+            ref value_key,
+        } => crate::de::adj::map::deserialize_seed::<String, str, _, _, _>(
+            deserializer,
+            tag_key,
+            value_key,
+            seed_factory,
+            tag_seed,
+        ),
+        TagFormat::AdjStruct {
+            name: Cow::Borrowed(name),
+            tag_key: Cow::Borrowed(tag_key),
+            value_key: Cow::Borrowed(value_key),
+        } => crate::de::adj::struc::deserialize_seed(
+            deserializer,
+            name,
+            tag_key,
+            value_key,
+            seed_factory,
+            tag_seed,
+        ),
+        TagFormat::AdjStruct { .. } => Err(serde::de::Error::custom(OWNED_ADJ_STRUCT)),
+    }
+}
@@ -5,8 +5,8 @@
 
 use crate::de::seed::SeedFactory;
 
-use std::fmt;
-use std::marker::PhantomData;
+use core::fmt;
+use core::marker::PhantomData;
 
 use serde;
 
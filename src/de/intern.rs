@@ -0,0 +1,246 @@
+//! Deserialization of interned tags.
+//!
+//! See [`ser::intern`](crate::ser::intern) for a description of tag
+//! interning. Interned tags are deserialized as [`Tag`], which is resolved by
+//! the [`Interned`] seed-factory wrapper, recording new tags in and looking up
+//! ids from a [`Table`].
+//!
+//! As a tag may either be a string or an id, deserialization of interned tags
+//! requires a self-describing data format.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::intern::{Interned, Table};
+//! use serde_tagged::de::WithoutTag;
+//!
+//! # fn main() {
+//! let table = Table::new();
+//! let stream = r#"["circle", 1] ["rect", 2] [0, 3]"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(stream);
+//! let mut values = Vec::new();
+//!
+//! for _ in 0..3 {
+//!     let factory = Interned::new(&table, WithoutTag::new());
+//!     let value: i32 = serde_tagged::de::adj::tuple::deserialize(&mut de, factory).unwrap();
+//!     values.push(value);
+//! }
+//!
+//! assert_eq!(values, vec![1, 2, 3]);
+//! assert_eq!(table.tag(0).as_deref(), Some("circle"));
+//! # }
+//! ```
+
+use crate::de::seed::SeedFactory;
+use crate::util::TagString;
+
+use core::cell::RefCell;
+use core::fmt;
+
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde;
+
+
+/// An interned tag, i.e. either the first occurrence of a tag or the id of a
+/// previously deserialized tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tag<'de> {
+    /// The first occurrence of a tag.
+    Tag(TagString<'de>),
+
+    /// The id of a previously deserialized tag.
+    Id(u64),
+}
+
+impl<'de> serde::Deserialize<'de> for Tag<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(TagVisitor)
+    }
+}
+
+struct TagVisitor;
+
+impl<'de> serde::de::Visitor<'de> for TagVisitor {
+    type Value = Tag<'de>;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a tag or tag id")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Tag::Id(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match u64::try_from(value) {
+            Ok(value) => Ok(Tag::Id(value)),
+            Err(_) => Err(E::invalid_value(
+                serde::de::Unexpected::Signed(value),
+                &self,
+            )),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match parse_id(value) {
+            Some(id) => Ok(Tag::Id(id)),
+            None => Ok(Tag::Tag(TagString::from(value.to_string()))),
+        }
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match parse_id(value) {
+            Some(id) => Ok(Tag::Id(id)),
+            None => Ok(Tag::Tag(TagString::from(value))),
+        }
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match parse_id(&value) {
+            Some(id) => Ok(Tag::Id(id)),
+            None => Ok(Tag::Tag(TagString::from(value))),
+        }
+    }
+}
+
+/// Parses ids represented as strings, e.g. map keys in JSON.
+fn parse_id(value: &str) -> Option<u64> {
+    if crate::ser::intern::is_id(value) {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+
+/// A table recording deserialized tags in order of their first occurrence.
+///
+/// Each tag is stored once as `Rc<str>`, shared between the lookup by id and
+/// the lookup by tag.
+/// See the [module documentation](crate::de::intern) for details.
+#[derive(Debug, Default)]
+pub struct Table {
+    tags: RefCell<Vec<Rc<str>>>,
+    ids:  RefCell<BTreeMap<Rc<str>, u64>>,
+}
+
+impl Table {
+    /// Creates a new, empty table.
+    pub fn new() -> Self {
+        Table {
+            tags: RefCell::new(Vec::new()),
+            ids:  RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the tag with the given id, or `None` if no tag has been
+    /// assigned this id yet.
+    pub fn tag(&self, id: u64) -> Option<Rc<str>> {
+        let tags = self.tags.borrow();
+        usize::try_from(id).ok().and_then(|i| tags.get(i)).cloned()
+    }
+
+    /// Returns the id assigned to the given tag, or `None` if the tag has not
+    /// been recorded yet.
+    pub fn id(&self, tag: &str) -> Option<u64> {
+        self.ids.borrow().get(tag).copied()
+    }
+
+    /// Records the given tag, assigning it the next free id unless it has
+    /// already been recorded.
+    fn insert(&self, tag: &str) {
+        let mut ids = self.ids.borrow_mut();
+
+        if !ids.contains_key(tag) {
+            let mut tags = self.tags.borrow_mut();
+            let tag: Rc<str> = Rc::from(tag);
+
+            ids.insert(tag.clone(), tags.len() as u64);
+            tags.push(tag);
+        }
+    }
+
+    /// Returns the number of recorded tags.
+    pub fn len(&self) -> usize {
+        self.tags.borrow().len()
+    }
+
+    /// Returns `true` if no tags have been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.tags.borrow().is_empty()
+    }
+}
+
+
+/// A [`SeedFactory`] wrapper resolving interned tags via a [`Table`].
+///
+/// New tags are recorded in the table, ids are resolved to the recorded tags.
+/// Tags that have already been recorded keep their id. The resolved tag is
+/// then passed to the wrapped [`SeedFactory`].
+pub struct Interned<'t, F> {
+    table:        &'t Table,
+    seed_factory: F,
+}
+
+impl<'t, F> Interned<'t, F> {
+    /// Creates a new wrapper around the given [`SeedFactory`], using the given
+    /// table.
+    pub fn new(table: &'t Table, seed_factory: F) -> Self {
+        Interned {
+            table,
+            seed_factory,
+        }
+    }
+}
+
+impl<'t, 'de, F> SeedFactory<'de, Tag<'de>> for Interned<'t, F>
+where
+    F: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = F::Value;
+    type Seed = F::Seed;
+
+    fn seed<E>(self, tag: Tag<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        let tag = match tag {
+            Tag::Tag(tag) => {
+                self.table.insert(&tag);
+                tag
+            },
+            Tag::Id(id) => match self.table.tag(id) {
+                Some(tag) => TagString::Owned(tag.to_string()),
+                None => return Err(E::custom(format_args!("unknown tag id `{}`", id))),
+            },
+        };
+
+        self.seed_factory.seed(tag)
+    }
+}
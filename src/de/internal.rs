@@ -11,8 +11,11 @@
 use crate::de::seed::SeedFactory;
 use crate::util::de::content::{Content, ContentDeserializer, ContentVisitor};
 
-use std;
-use std::marker::PhantomData;
+use core;
+use core::marker::PhantomData;
+
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use serde;
 
@@ -79,10 +82,10 @@ where
 ///
 /// See [`de`](crate::de) for more information on
 /// [`SeedFactory`] and implementations thereof.
-pub struct Visitor<F, S> {
+pub struct Visitor<F, S, K = &'static str> {
     seed_factory: F,
     tag_seed:     S,
-    tag_key:      &'static str,
+    tag_key:      K,
 }
 
 impl<F, S> Visitor<F, S> {
@@ -97,14 +100,25 @@ impl<F, S> Visitor<F, S> {
     }
 }
 
-impl<'de, F, S> serde::de::Visitor<'de> for Visitor<F, S>
+impl<'k, F, S> Visitor<F, S, &'k str> {
+    /// Creates a new visitor with a tag-key that is not `'static`.
+    pub(crate) fn with_key(tag_key: &'k str, seed_factory: F, tag_seed: S) -> Self {
+        Visitor {
+            seed_factory,
+            tag_seed,
+            tag_key,
+        }
+    }
+}
+
+impl<'de, F, S> serde::de::Visitor<'de> for Visitor<F, S, &str>
 where
     F: SeedFactory<'de, S::Value>,
     S: serde::de::DeserializeSeed<'de>,
 {
     type Value = F::Value;
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a tagged value")
     }
 
@@ -136,20 +150,20 @@ where
 }
 
 
-struct TaggedValueVisitor {
-    tag_key: &'static str,
+struct TaggedValueVisitor<'k> {
+    tag_key: &'k str,
 }
 
-impl TaggedValueVisitor {
-    fn new(tag_key: &'static str) -> Self {
+impl<'k> TaggedValueVisitor<'k> {
+    fn new(tag_key: &'k str) -> Self {
         TaggedValueVisitor { tag_key }
     }
 }
 
-impl<'de> serde::de::Visitor<'de> for TaggedValueVisitor {
+impl<'de, 'k> serde::de::Visitor<'de> for TaggedValueVisitor<'k> {
     type Value = (Content<'de>, Content<'de>);
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a tagged value")
     }
 
@@ -158,11 +172,11 @@ impl<'de> serde::de::Visitor<'de> for TaggedValueVisitor {
         A: serde::de::SeqAccess<'de>,
     {
         use serde::de::value::SeqAccessDeserializer;
-        use serde::de::{Deserialize, Error};
+        use serde::de::Deserialize;
 
         let tag: Content = seq
             .next_element()?
-            .ok_or_else(|| Error::missing_field(self.tag_key))?;
+            .ok_or_else(|| missing_field(self.tag_key))?;
 
         let val = Content::deserialize(SeqAccessDeserializer::new(seq))?;
 
@@ -182,7 +196,10 @@ impl<'de> serde::de::Visitor<'de> for TaggedValueVisitor {
             match key {
                 TagOrValue::Tag => {
                     if tag.is_some() {
-                        return Err(Error::duplicate_field(self.tag_key));
+                        return Err(Error::custom(format_args!(
+                            "duplicate field `{}`",
+                            self.tag_key
+                        )));
                     }
                     tag = Some(map.next_value()?);
                 },
@@ -192,30 +209,37 @@ impl<'de> serde::de::Visitor<'de> for TaggedValueVisitor {
             }
         }
 
-        let tag = tag.ok_or_else(|| Error::missing_field(self.tag_key))?;
+        let tag = tag.ok_or_else(|| missing_field(self.tag_key))?;
 
         Ok((tag, Content::Map(val)))
     }
 }
 
 
+/// Returns the error for a missing tag, equivalent to `Error::missing_field`
+/// but without requiring a `'static` key.
+fn missing_field<E: serde::de::Error>(tag_key: &str) -> E {
+    E::custom(format_args!("missing field `{}`", tag_key))
+}
+
+
 enum TagOrValue<'de> {
     Tag,
     Value(Content<'de>),
 }
 
 
-struct TagOrValueSeed {
-    tag_key: &'static str,
+struct TagOrValueSeed<'k> {
+    tag_key: &'k str,
 }
 
-impl TagOrValueSeed {
-    fn new(tag_key: &'static str) -> Self {
+impl<'k> TagOrValueSeed<'k> {
+    fn new(tag_key: &'k str) -> Self {
         TagOrValueSeed { tag_key }
     }
 }
 
-impl<'de> serde::de::DeserializeSeed<'de> for TagOrValueSeed {
+impl<'de, 'k> serde::de::DeserializeSeed<'de> for TagOrValueSeed<'k> {
     type Value = TagOrValue<'de>;
 
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
@@ -226,10 +250,10 @@ impl<'de> serde::de::DeserializeSeed<'de> for TagOrValueSeed {
     }
 }
 
-impl<'de> serde::de::Visitor<'de> for TagOrValueSeed {
+impl<'de, 'k> serde::de::Visitor<'de> for TagOrValueSeed<'k> {
     type Value = TagOrValue<'de>;
 
-    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(fmt, "a tag `{}` or any other value", self.tag_key)
     }
 
@@ -1,8 +1,23 @@
 //! Deserialization of tagged values.
 
 pub mod adj;
+pub mod auto;
+pub mod dynamic;
 pub mod external;
+pub mod intern;
 pub mod internal;
+pub mod native;
+pub mod nominal;
+pub mod normalize;
+pub mod readable;
+pub mod registry;
+pub mod variant;
+pub mod version;
 
 mod seed;
 pub use self::seed::*;
+
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+pub use self::scope::*;
@@ -0,0 +1,116 @@
+//! Deserialization of tagged values using the native tag syntax of the data
+//! format.
+//!
+//! See [`ser::native`](crate::ser::native) for a description of this tagging
+//! format and the protocol used to communicate with data formats.
+
+use crate::de::seed::SeedFactory;
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde;
+
+
+/// Deserialize a value tagged using the native tag syntax of the data format.
+///
+/// Requests a newtype struct with the given name from the deserializer, e.g.
+/// [`NAME`](crate::ser::native::NAME). Data formats not supporting the
+/// protocol described in [`ser::native`](crate::ser::native) may instead
+/// provide the tagged value as tuple, i.e. in the representation of [adjacent
+/// tagging using tuples](crate::de::adj::tuple), which is accepted as well.
+///
+/// The deserializer controls the underlying data format while the seed-factory
+/// specifies the instructions (depending on the tag) on how the value should be
+/// deserialized.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub fn deserialize<'de, T, D, F>(
+    deserializer: D,
+    name: &'static str,
+    seed_factory: F,
+) -> Result<F::Value, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, T>,
+{
+    deserialize_seed(deserializer, name, seed_factory, PhantomData::<T>)
+}
+
+
+/// Deserialize a value tagged using the native tag syntax of the data format,
+/// using the given `DeserializeSeed` for the tag.
+///
+/// See [`deserialize`] for more information.
+pub fn deserialize_seed<'de, D, F, S>(
+    deserializer: D,
+    name: &'static str,
+    seed_factory: F,
+    tag_seed: S,
+) -> Result<F::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, S::Value>,
+    S: serde::de::DeserializeSeed<'de>,
+{
+    deserializer.deserialize_newtype_struct(name, Visitor::new(seed_factory, tag_seed))
+}
+
+
+/// A visitor that can be used to deserialize a value tagged using the native
+/// tag syntax of the data format.
+///
+/// This visitor accepts either a newtype struct wrapping a tuple of tag and
+/// value, or such a tuple directly.
+///
+/// The [`SeedFactory`] provided to this visitor provides a
+/// `serde::de::DeserializeSeed` implementation depending on the tag, which then
+/// determines how the value is going to be deserialized.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub struct Visitor<F, S> {
+    seed_factory: F,
+    tag_seed:     S,
+}
+
+impl<F, S> Visitor<F, S> {
+    /// Creates a new visitor with the given [`SeedFactory`].
+    pub fn new(seed_factory: F, tag_seed: S) -> Self {
+        Visitor {
+            seed_factory,
+            tag_seed,
+        }
+    }
+}
+
+impl<'de, F, S> serde::de::Visitor<'de> for Visitor<F, S>
+where
+    F: SeedFactory<'de, S::Value>,
+    S: serde::de::DeserializeSeed<'de>,
+{
+    type Value = F::Value;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmtr,
+            "a natively tagged value or a tuple with exactly two elements"
+        )
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::de::adj::tuple::deserialize_seed(deserializer, self.seed_factory, self.tag_seed)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        crate::de::adj::tuple::Visitor::new(self.seed_factory, self.tag_seed).visit_seq(seq)
+    }
+}
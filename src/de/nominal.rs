@@ -0,0 +1,169 @@
+//! Deserialization of values tagged with their type name.
+//!
+//! See [`ser::nominal`](crate::ser::nominal) for a description of this
+//! tagging method. Values tagged this way can be deserialized with the
+//! functions of the respective format, e.g. [`de::dynamic`](crate::de::dynamic).
+//! The tags of the registry used for deserialization should be obtained via
+//! [`name`], so that the type names declared via serde (e.g. via
+//! `#[serde(rename = "...")]`) remain the single source of truth.
+//!
+//! # Example
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::{dynamic, nominal};
+//! use serde_tagged::format::TagFormat;
+//!
+//! use std::collections::BTreeMap;
+//! use std::marker::PhantomData;
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! #[serde(rename = "point")]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! # fn main() {
+//! let mut registry = BTreeMap::new();
+//! registry.insert(nominal::name::<Point>().unwrap(), PhantomData::<Point>);
+//!
+//! let format = TagFormat::internal("type");
+//! let json = r#"{"type":"point","x":1,"y":2}"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let point: Point = dynamic::deserialize(&mut de, &format, registry).unwrap();
+//!
+//! assert_eq!(point, Point { x: 1, y: 2 });
+//! # }
+//! ```
+
+use core::fmt;
+
+use serde;
+
+
+/// Returns the type name that `T` passes to the deserializer, or `None` if it
+/// does not pass any.
+///
+/// This is the name that [`ser::nominal`](crate::ser::nominal) emits as tag
+/// for values of this type. No data is deserialized, the deserialization is
+/// aborted as soon as the name is known.
+pub fn name<'de, T>() -> Option<&'static str>
+where
+    T: serde::Deserialize<'de>,
+{
+    match T::deserialize(NameDeserializer) {
+        Err(NameError::Name(name)) => Some(name),
+        _ => None,
+    }
+}
+
+
+/// The result of a [`NameDeserializer`], returned as error to abort
+/// deserialization early.
+#[derive(Debug)]
+enum NameError {
+    Name(&'static str),
+    Unnamed,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NameError::Name(name) => write!(f, "type name `{}`", name),
+            NameError::Unnamed => write!(f, "type without name"),
+        }
+    }
+}
+
+impl serde::de::StdError for NameError {}
+
+impl serde::de::Error for NameError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        NameError::Unnamed
+    }
+}
+
+
+/// A deserializer capturing the type name of the type requesting
+/// deserialization.
+struct NameDeserializer;
+
+impl<'de> serde::Deserializer<'de> for NameDeserializer {
+    type Error = NameError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Unnamed)
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, NameError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        Err(NameError::Name(name))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit seq tuple map identifier ignored_any
+    }
+}
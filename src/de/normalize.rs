@@ -0,0 +1,191 @@
+//! Normalization of string-based tags before lookup.
+//!
+//! Tags of human-edited documents (e.g. configuration files) may not exactly
+//! match the tags stored in a registry, e.g. `"a"`, `"A"`, or `" A "` might
+//! all be intended to refer to the same type `"A"`. This module provides the
+//! [`Normalized`] adapter, which applies a normalization policy ([`Normalize`])
+//! to a [`TagString`] before passing it on to the wrapped [`SeedFactory`],
+//! e.g. one of the `BTreeMap` or `HashMap` implementations.
+//!
+//! Normalization does not allocate unless the tag needs to be case-folded, in
+//! which case an allocation is only performed if the tag is not already in the
+//! requested case.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::normalize::{Case, Normalize};
+//!
+//! # fn main() {
+//! let policy = Normalize::new()
+//!     .trim()
+//!     .strip_namespace('.')
+//!     .case(Case::Upper);
+//!
+//! assert_eq!(&*policy.apply(" com.example.a ".into()), "A");
+//! # }
+//! ```
+
+use crate::de::seed::SeedFactory;
+use crate::util::TagString;
+
+use serde;
+
+
+/// The case to which tags are folded during normalization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// Preserve the case of the tag.
+    Preserve,
+
+    /// Fold the tag to lower-case.
+    Lower,
+
+    /// Fold the tag to upper-case.
+    Upper,
+}
+
+
+/// A normalization policy for string-based tags.
+///
+/// The normalization steps are applied in the following order: trimming of
+/// whitespace, stripping of the namespace-prefix, case folding. A newly created
+/// policy does not perform any normalization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Normalize {
+    trim:      bool,
+    namespace: Option<char>,
+    case:      Case,
+}
+
+impl Normalize {
+    /// Creates a new policy that does not perform any normalization.
+    pub fn new() -> Self {
+        Normalize {
+            trim:      false,
+            namespace: None,
+            case:      Case::Preserve,
+        }
+    }
+
+    /// Enables trimming of leading and trailing whitespace.
+    pub fn trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// Enables stripping of namespace-prefixes, where `separator` separates
+    /// the namespace from the actual tag (e.g. `com.example.A` becomes `A`
+    /// with a separator of `'.'`).
+    pub fn strip_namespace(mut self, separator: char) -> Self {
+        self.namespace = Some(separator);
+        self
+    }
+
+    /// Sets the case to which tags are folded.
+    pub fn case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Applies this policy to the given tag.
+    ///
+    /// Trimming and stripping of namespaces will never allocate. Case folding
+    /// will only allocate if the tag is not already in the requested case.
+    pub fn apply<'a>(&self, tag: TagString<'a>) -> TagString<'a> {
+        let (start, end) = if self.trim {
+            let end = tag.trim_end().len();
+            (end - tag[..end].trim_start().len(), end)
+        } else {
+            (0, tag.len())
+        };
+
+        let start = match self.namespace {
+            Some(sep) => match tag[start..end].rfind(sep) {
+                Some(pos) => start + pos + sep.len_utf8(),
+                None => start,
+            },
+            None => start,
+        };
+
+        let tag = tag.into_slice(start, end);
+
+        // Some characters change their case without being upper- or lowercase
+        // (e.g. titlecase letters), thus check the mapping itself.
+        match self.case {
+            Case::Lower if tag.chars().any(|c| c.to_lowercase().ne(Some(c))) => {
+                tag.to_lowercase().into()
+            },
+            Case::Upper if tag.chars().any(|c| c.to_uppercase().ne(Some(c))) => {
+                tag.to_uppercase().into()
+            },
+            _ => tag,
+        }
+    }
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Normalize::new()
+    }
+}
+
+
+/// A [`SeedFactory`] adapter that normalizes string-based tags before passing
+/// them on to the wrapped factory.
+///
+/// See [`Normalize`] for the available normalization steps.
+pub struct Normalized<F> {
+    factory: F,
+    policy:  Normalize,
+}
+
+impl<F> Normalized<F> {
+    /// Creates a new adapter applying the given policy to all tags before
+    /// passing them on to the given factory.
+    pub fn new(factory: F, policy: Normalize) -> Self {
+        Normalized { factory, policy }
+    }
+
+    /// Returns the normalization policy used by this adapter.
+    pub fn policy(&self) -> &Normalize {
+        &self.policy
+    }
+
+    /// Returns a reference to the wrapped [`SeedFactory`].
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+}
+
+impl<'de, F> SeedFactory<'de, TagString<'de>> for Normalized<F>
+where
+    F: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = F::Value;
+    type Seed = F::Seed;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.factory.seed(self.policy.apply(tag))
+    }
+}
+
+impl<'r, 'de, F> SeedFactory<'de, TagString<'de>> for &'r Normalized<F>
+where
+    &'r F: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = <&'r F as SeedFactory<'de, TagString<'de>>>::Value;
+    type Seed = <&'r F as SeedFactory<'de, TagString<'de>>>::Seed;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.factory.seed(self.policy.apply(tag))
+    }
+}
@@ -0,0 +1,124 @@
+//! Deserialization of tags depending on whether the data format is
+//! human-readable.
+//!
+//! See [`ser::readable`](crate::ser::readable) for a description of this tag
+//! representation. The [`TagSeed`] provided in this module can be passed to
+//! the `deserialize_seed` function of every format in [`de`](crate::de).
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::WithTag;
+//! use serde_tagged::de::readable::TagSeed;
+//! use serde_tagged::format::TagIds;
+//! use serde_tagged::util::TagString;
+//!
+//! const IDS: TagIds = TagIds::new(&[("circle", 1), ("rect", 2)]);
+//!
+//! # fn main() {
+//! let json = r#"{"rect":42}"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let (tag, value): (TagString, i32) =
+//!     serde_tagged::de::external::deserialize_seed(&mut de, WithTag::new(), TagSeed::new(&IDS))
+//!         .unwrap();
+//!
+//! assert_eq!(&*tag, "rect");
+//! assert_eq!(value, 42);
+//! # }
+//! ```
+
+use crate::format::TagIds;
+use crate::util::TagString;
+
+use core::fmt;
+
+use serde;
+
+
+/// A `DeserializeSeed` for tags serialized either as string or as numeric id,
+/// depending on whether the data format is human-readable.
+///
+/// The form reported by `Deserializer::is_human_readable` is requested from
+/// the deserializer, however, both forms are accepted if the deserializer
+/// provides the other one (e.g. for buffered values). The tag is resolved via
+/// the given [`TagIds`] and returned as [`TagString`], thus the standard
+/// [`SeedFactory`](crate::de::SeedFactory) implementations can be used.
+/// Unknown tags and ids are rejected.
+#[derive(Clone, Copy, Debug)]
+pub struct TagSeed<'a> {
+    ids: &'a TagIds,
+}
+
+impl<'a> TagSeed<'a> {
+    /// Creates a new seed using the given id assignment.
+    pub fn new(ids: &'a TagIds) -> Self {
+        TagSeed { ids }
+    }
+}
+
+impl<'a, 'de> serde::de::DeserializeSeed<'de> for TagSeed<'a> {
+    type Value = TagString<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(self)
+        } else {
+            deserializer.deserialize_u64(self)
+        }
+    }
+}
+
+impl<'a, 'de> serde::de::Visitor<'de> for TagSeed<'a> {
+    type Value = TagString<'de>;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a tag or tag id")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.ids
+            .tag(value)
+            .map(TagString::from)
+            .ok_or_else(|| E::custom(format_args!("unknown tag id `{}`", value)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::custom(format_args!("unknown tag id `{}`", value))),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.ids
+            .id(value)
+            .map(|(_, tag)| TagString::from(tag))
+            .ok_or_else(|| E::custom(format_args!("unknown tag `{}`", value)))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match core::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Bytes(value), &self)),
+        }
+    }
+}
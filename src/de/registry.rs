@@ -0,0 +1,838 @@
+//! Registries of `DeserializeSeed` implementations.
+//!
+//! The [`SeedFactory`] implementations for the standard map types provided in
+//! [`de`](crate::de) are static once built. This module provides registries
+//! for use-cases that go beyond this, as well as operations to combine
+//! registries (see [`Merge`]).
+//!
+//! The [`Registry`] provided in this module additionally stores information
+//! about the registered types, which allows it to be inspected, e.g. to list
+//! the accepted tags or to validate documents before deserializing them.
+//!
+//! The [`ContextRegistry`] passes a user-provided context to its seeds, which
+//! allows stateful deserialization (e.g. using a string interner).
+
+use crate::de::seed::{unknown_tag, unknown_tag_because, FnPtrSeed, SeedFactory};
+#[cfg(feature = "erased")]
+use crate::de::seed::{BoxFnSeedWithContext, SeedWithContext};
+use crate::format::TagFormat;
+use crate::util::TagString;
+
+use core;
+use core::fmt::Display;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::{PoisonError, RwLock};
+
+use serde;
+
+
+/// A registry storing information about the registered types in addition to
+/// their seeds.
+///
+/// For each tag, this registry stores an [`Entry`] containing the seed, the
+/// name of the Rust type produced by it, and the tagging format in which
+/// values with this tag are expected. This information can be queried via
+/// [`entries`](Registry::entries) or [`types`](Registry::types), the latter
+/// of which yields serializable [`TypeInfo`] objects.
+///
+/// The [`SeedFactory`] implementations of this registry behave like the ones
+/// for the standard map types.
+pub struct Registry<S> {
+    entries: BTreeMap<String, Entry<S>>,
+}
+
+impl<S> Registry<S> {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Registry {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the given entry for the given tag, returning the entry that
+    /// has previously been registered for this tag (if any).
+    pub fn insert<T>(&mut self, tag: T, entry: Entry<S>) -> Option<Entry<S>>
+    where
+        T: Into<String>,
+    {
+        self.entries.insert(tag.into(), entry)
+    }
+
+    /// Removes the entry for the given tag, returning it if it has been
+    /// registered.
+    pub fn remove(&mut self, tag: &str) -> Option<Entry<S>> {
+        self.entries.remove(tag)
+    }
+
+    /// Returns the entry registered for the given tag.
+    pub fn get(&self, tag: &str) -> Option<&Entry<S>> {
+        self.entries.get(tag)
+    }
+
+    /// Returns `true` if an entry is registered for the given tag.
+    pub fn contains(&self, tag: &str) -> bool {
+        self.entries.contains_key(tag)
+    }
+
+    /// Returns the number of registered entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over all registered tags, in ascending order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Returns an iterator over all registered tags and their entries, in
+    /// ascending order of the tags.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Entry<S>)> {
+        self.entries
+            .iter()
+            .map(|(tag, entry)| (tag.as_str(), entry))
+    }
+
+    /// Returns an iterator over information about all registered types, in
+    /// ascending order of their tags.
+    pub fn types(&self) -> impl Iterator<Item = TypeInfo<'_>> {
+        self.entries().map(|(tag, entry)| TypeInfo {
+            tag,
+            type_name: entry.type_name,
+            format: &entry.format,
+        })
+    }
+}
+
+impl<S> Default for Registry<S> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<S> IntoIterator for Registry<S> {
+    type Item = (String, Entry<S>);
+    type IntoIter = alloc::collections::btree_map::IntoIter<String, Entry<S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'de, V, S> SeedFactory<'de, TagString<'de>> for Registry<S>
+where
+    S: serde::de::DeserializeSeed<'de, Value = V>,
+{
+    type Value = V;
+    type Seed = S;
+
+    fn seed<E>(mut self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.entries
+            .remove(tag.as_ref())
+            .map(|entry| entry.seed)
+            .ok_or_else(|| unknown_tag(tag))
+    }
+}
+
+impl<'r, 'de, V, S> SeedFactory<'de, TagString<'de>> for &'r Registry<S>
+where
+    &'r S: serde::de::DeserializeSeed<'de, Value = V>,
+{
+    type Value = V;
+    type Seed = &'r S;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.entries
+            .get(tag.as_ref())
+            .map(|entry| &entry.seed)
+            .ok_or_else(|| unknown_tag(tag))
+    }
+}
+
+
+/// An entry of a [`Registry`].
+pub struct Entry<S> {
+    seed:      S,
+    type_name: &'static str,
+    format:    TagFormat,
+}
+
+impl<S> Entry<S> {
+    /// Creates a new entry for values of type `T`, expected in the given
+    /// format, which are deserialized using the given seed.
+    pub fn new<T>(format: TagFormat, seed: S) -> Self
+    where
+        T: ?Sized,
+    {
+        Entry {
+            seed,
+            type_name: core::any::type_name::<T>(),
+            format,
+        }
+    }
+
+    /// Returns the seed of this entry.
+    pub fn seed(&self) -> &S {
+        &self.seed
+    }
+
+    /// Returns the name of the Rust type produced by the seed of this entry.
+    ///
+    /// See `std::any::type_name` for details on this name.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the tagging format in which values of this entry are expected.
+    pub fn format(&self) -> &TagFormat {
+        &self.format
+    }
+
+    /// Consumes this entry, returning its seed.
+    pub fn into_seed(self) -> S {
+        self.seed
+    }
+}
+
+
+/// Serializable information about a type registered in a [`Registry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeInfo<'a> {
+    /// The tag of the type.
+    pub tag: &'a str,
+
+    /// The name of the Rust type.
+    pub type_name: &'static str,
+
+    /// The tagging format in which values of this type are expected.
+    pub format: &'a TagFormat,
+}
+
+impl<'a> serde::Serialize for TypeInfo<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("TypeInfo", 3)?;
+        state.serialize_field("tag", self.tag)?;
+        state.serialize_field("type", self.type_name)?;
+        state.serialize_field("format", &self.format)?;
+        state.end()
+    }
+}
+
+
+/// A thread-safe registry that can be modified while it is in use.
+///
+/// This registry allows seeds to be registered and unregistered at runtime,
+/// e.g. when loading and unloading plugins, while other threads deserialize
+/// values using this registry. Seeds are stored reference-counted, thus a seed
+/// that has been looked up before it is unregistered remains valid until the
+/// deserialization using it has finished. Lookups of a tag after its seed has
+/// been unregistered will fail with an unknown-tag error.
+///
+/// The [`SeedFactory`] implementation is provided by reference. It requires
+/// that a reference to the stored seed type implements `DeserializeSeed`,
+/// which is, for example, the case for [`BoxFnSeed`](crate::de::BoxFnSeed).
+/// For the registry to be shared between threads, the stored seed type is
+/// required to implement `Send` and `Sync`.
+#[cfg(feature = "std")]
+pub struct SharedRegistry<S> {
+    seeds: RwLock<BTreeMap<String, Arc<S>>>,
+}
+
+#[cfg(feature = "std")]
+impl<S> SharedRegistry<S> {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        SharedRegistry {
+            seeds: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers the given seed for the given tag, returning the seed that has
+    /// previously been registered for this tag (if any).
+    pub fn register<T>(&self, tag: T, seed: S) -> Option<Arc<S>>
+    where
+        T: Into<String>,
+    {
+        let mut seeds = self.seeds.write().unwrap_or_else(PoisonError::into_inner);
+        seeds.insert(tag.into(), Arc::new(seed))
+    }
+
+    /// Unregisters the seed for the given tag, returning the seed if it has
+    /// been registered.
+    pub fn unregister(&self, tag: &str) -> Option<Arc<S>> {
+        let mut seeds = self.seeds.write().unwrap_or_else(PoisonError::into_inner);
+        seeds.remove(tag)
+    }
+
+    /// Returns the seed registered for the given tag.
+    pub fn get(&self, tag: &str) -> Option<Arc<S>> {
+        let seeds = self.seeds.read().unwrap_or_else(PoisonError::into_inner);
+        seeds.get(tag).cloned()
+    }
+
+    /// Returns `true` if a seed is registered for the given tag.
+    pub fn contains(&self, tag: &str) -> bool {
+        let seeds = self.seeds.read().unwrap_or_else(PoisonError::into_inner);
+        seeds.contains_key(tag)
+    }
+
+    /// Returns a snapshot of all registered tags, in ascending order.
+    pub fn tags(&self) -> Vec<String> {
+        let seeds = self.seeds.read().unwrap_or_else(PoisonError::into_inner);
+        seeds.keys().cloned().collect()
+    }
+
+    /// Returns the number of registered seeds.
+    pub fn len(&self) -> usize {
+        let seeds = self.seeds.read().unwrap_or_else(PoisonError::into_inner);
+        seeds.len()
+    }
+
+    /// Returns `true` if no seeds are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S> Default for SharedRegistry<S> {
+    fn default() -> Self {
+        SharedRegistry::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, S, V> SeedFactory<'de, TagString<'de>> for &SharedRegistry<S>
+where
+    for<'a> &'a S: serde::de::DeserializeSeed<'de, Value = V>,
+{
+    type Value = V;
+    type Seed = SharedSeed<S>;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.get(&tag)
+            .map(SharedSeed)
+            .ok_or_else(|| unknown_tag(tag))
+    }
+}
+
+
+/// A `DeserializeSeed` implementation for a seed shared via reference
+/// counting.
+///
+/// Instances of this type are created by the [`SeedFactory`] implementation
+/// of [`SharedRegistry`].
+#[cfg(feature = "std")]
+pub struct SharedSeed<S>(Arc<S>);
+
+#[cfg(feature = "std")]
+impl<'de, S, V> serde::de::DeserializeSeed<'de> for SharedSeed<S>
+where
+    for<'a> &'a S: serde::de::DeserializeSeed<'de, Value = V>,
+{
+    type Value = V;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        (&*self.0).deserialize(deserializer)
+    }
+}
+
+
+/// A registry of function pointers taking a value buffered as type `D`.
+///
+/// This registry does not require the `erased` feature, but requires a
+/// self-describing data format. See [`FnPtrSeed`] for details.
+pub type FnRegistry<D, V> = BTreeMap<&'static str, FnPtrSeed<D, V>>;
+
+
+/// A registry of seeds that are provided with a context during
+/// deserialization.
+///
+/// This registry stores [`BoxFnSeedWithContext`] closures, which receive a
+/// mutable reference to a context of type `C` in addition to the
+/// deserializer. The context is provided for a single deserialization call via
+/// [`with_context`](ContextRegistry::with_context), which returns the actual
+/// [`SeedFactory`]. This allows the per-type deserialization functions to
+/// access shared state (e.g. string interners, arenas, or configuration)
+/// without resorting to globals.
+#[cfg(feature = "erased")]
+pub struct ContextRegistry<C: ?Sized, V> {
+    seeds: BTreeMap<String, BoxFnSeedWithContext<C, V>>,
+}
+
+#[cfg(feature = "erased")]
+impl<C: ?Sized, V> ContextRegistry<C, V> {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        ContextRegistry {
+            seeds: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the given seed for the given tag, returning the seed that has
+    /// previously been registered for this tag (if any).
+    pub fn insert<T>(
+        &mut self,
+        tag: T,
+        seed: BoxFnSeedWithContext<C, V>,
+    ) -> Option<BoxFnSeedWithContext<C, V>>
+    where
+        T: Into<String>,
+    {
+        self.seeds.insert(tag.into(), seed)
+    }
+
+    /// Removes the seed for the given tag, returning it if it has been
+    /// registered.
+    pub fn remove(&mut self, tag: &str) -> Option<BoxFnSeedWithContext<C, V>> {
+        self.seeds.remove(tag)
+    }
+
+    /// Returns the seed registered for the given tag.
+    pub fn get(&self, tag: &str) -> Option<&BoxFnSeedWithContext<C, V>> {
+        self.seeds.get(tag)
+    }
+
+    /// Returns `true` if a seed is registered for the given tag.
+    pub fn contains(&self, tag: &str) -> bool {
+        self.seeds.contains_key(tag)
+    }
+
+    /// Returns the number of registered seeds.
+    pub fn len(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// Returns `true` if no seeds are registered.
+    pub fn is_empty(&self) -> bool {
+        self.seeds.is_empty()
+    }
+
+    /// Returns an iterator over all registered tags, in ascending order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.seeds.keys().map(String::as_str)
+    }
+
+    /// Returns a [`SeedFactory`] passing the given context to the seeds of
+    /// this registry.
+    pub fn with_context<'c>(&self, context: &'c mut C) -> WithContext<'_, 'c, C, V> {
+        WithContext {
+            registry: self,
+            context,
+        }
+    }
+}
+
+#[cfg(feature = "erased")]
+impl<C: ?Sized, V> Default for ContextRegistry<C, V> {
+    fn default() -> Self {
+        ContextRegistry::new()
+    }
+}
+
+
+/// A [`SeedFactory`] passing a context to the seeds of a [`ContextRegistry`].
+///
+/// Instances of this type are created by [`ContextRegistry::with_context`].
+#[cfg(feature = "erased")]
+pub struct WithContext<'r, 'c, C: ?Sized, V> {
+    registry: &'r ContextRegistry<C, V>,
+    context:  &'c mut C,
+}
+
+#[cfg(feature = "erased")]
+impl<'r, 'c, 'de, C: ?Sized, V> SeedFactory<'de, TagString<'de>> for WithContext<'r, 'c, C, V> {
+    type Value = V;
+    type Seed = SeedWithContext<'r, 'c, C, V>;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        match self.registry.get(&tag) {
+            Some(seed) => Ok(seed.with_context(self.context)),
+            None => Err(unknown_tag(tag)),
+        }
+    }
+}
+
+
+/// A registry routing tags to sub-registries based on their namespace.
+///
+/// Tags of the form `<namespace><separator><rest>` (e.g. `vendor.module.Type`
+/// with a separator of `'.'`) are routed to the sub-registry registered for
+/// `<namespace>`, which then receives the remaining part of the tag (e.g.
+/// `module.Type`). This allows independent parties to own their namespace.
+/// Sub-registries can be any [`SeedFactory`] for string-based tags, including
+/// further namespaced registries for nested namespaces.
+///
+/// Splitting the tag does not allocate: borrowed tags are sliced and owned
+/// tags are truncated in place.
+pub struct Namespaced<R> {
+    separator:  char,
+    namespaces: BTreeMap<String, R>,
+}
+
+impl<R> Namespaced<R> {
+    /// Creates a new registry without any namespaces, splitting tags at the
+    /// given separator.
+    pub fn new(separator: char) -> Self {
+        Namespaced {
+            separator,
+            namespaces: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the given sub-registry for the given namespace, returning the
+    /// sub-registry that has previously been registered for this namespace
+    /// (if any).
+    pub fn insert<N>(&mut self, namespace: N, registry: R) -> Option<R>
+    where
+        N: Into<String>,
+    {
+        self.namespaces.insert(namespace.into(), registry)
+    }
+
+    /// Removes the sub-registry for the given namespace, returning it if it has
+    /// been registered.
+    pub fn remove(&mut self, namespace: &str) -> Option<R> {
+        self.namespaces.remove(namespace)
+    }
+
+    /// Returns the sub-registry registered for the given namespace.
+    pub fn get(&self, namespace: &str) -> Option<&R> {
+        self.namespaces.get(namespace)
+    }
+
+    /// Returns the sub-registry registered for the given namespace.
+    pub fn get_mut(&mut self, namespace: &str) -> Option<&mut R> {
+        self.namespaces.get_mut(namespace)
+    }
+
+    /// Returns the separator used to split namespace and tag.
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    fn split<E>(&self, tag: &str) -> Result<usize, E>
+    where
+        E: serde::de::Error,
+    {
+        tag.find(self.separator)
+            .ok_or_else(|| unknown_tag_because(tag, "no namespace specified"))
+    }
+
+    fn unknown<E>(namespace: &str, tag: &str) -> E
+    where
+        E: serde::de::Error,
+    {
+        unknown_tag_because(tag, format_args!("unknown namespace `{}`", namespace))
+    }
+}
+
+impl<'de, R> SeedFactory<'de, TagString<'de>> for Namespaced<R>
+where
+    R: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = R::Value;
+    type Seed = R::Seed;
+
+    fn seed<E>(mut self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        let pos = self.split(&tag)?;
+
+        let registry = match self.namespaces.remove(&tag[..pos]) {
+            Some(registry) => registry,
+            None => return Err(Self::unknown(&tag[..pos], &tag)),
+        };
+
+        let len = tag.len();
+        registry.seed(tag.into_slice(pos + self.separator.len_utf8(), len))
+    }
+}
+
+impl<'r, 'de, R> SeedFactory<'de, TagString<'de>> for &'r Namespaced<R>
+where
+    &'r R: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = <&'r R as SeedFactory<'de, TagString<'de>>>::Value;
+    type Seed = <&'r R as SeedFactory<'de, TagString<'de>>>::Seed;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        let pos = self.split(&tag)?;
+
+        let registry = match self.namespaces.get(&tag[..pos]) {
+            Some(registry) => registry,
+            None => return Err(Namespaced::<R>::unknown(&tag[..pos], &tag)),
+        };
+
+        let len = tag.len();
+        registry.seed(tag.into_slice(pos + self.separator.len_utf8(), len))
+    }
+}
+
+
+/// The policy applied when merging registries containing the same tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Fail the merge operation without modifying the target registry.
+    Error,
+
+    /// Keep the seed that has been registered first.
+    KeepFirst,
+
+    /// Keep the seed that has been registered last.
+    KeepLast,
+}
+
+
+/// A report of a successful merge operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    duplicates: Vec<String>,
+}
+
+impl MergeReport {
+    /// Returns the duplicate tags encountered during the merge operation, in
+    /// the order in which they have been encountered.
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+
+    /// Returns `true` if no duplicate tags have been encountered.
+    pub fn is_clean(&self) -> bool {
+        self.duplicates.is_empty()
+    }
+}
+
+
+/// The error returned when merging registries with conflicting tags using the
+/// [`Conflict::Error`] policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeError {
+    duplicates: Vec<String>,
+}
+
+impl MergeError {
+    /// Returns the duplicate tags that caused this error.
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+}
+
+impl core::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "duplicate tags in registry: ")?;
+
+        for (i, tag) in self.duplicates.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "`{}`", tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl core::error::Error for MergeError {}
+
+
+/// Merge operations for registries.
+///
+/// Registries contributed by different sources (e.g. separate crates) can be
+/// combined using this trait. Tags registered in both registries are resolved
+/// according to the given [`Conflict`] policy and reported either in the
+/// returned [`MergeReport`] or, for [`Conflict::Error`], in the returned
+/// [`MergeError`]. In the latter case, the target registry is left unmodified.
+///
+/// Duplicates within the merged entries themselves are treated the same way
+/// as duplicates between target and merged entries.
+///
+/// This trait is implemented for the standard map types as well as for
+/// [`Registry`] and [`SharedRegistry`].
+pub trait Merge<K, S> {
+    /// Merges the given tag-seed pairs into this registry.
+    fn merge_from<I>(&mut self, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+    where
+        I: IntoIterator<Item = (K, S)>;
+}
+
+impl<K, S> Merge<K, S> for BTreeMap<K, S>
+where
+    K: Ord + Display,
+{
+    fn merge_from<I>(&mut self, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+    where
+        I: IntoIterator<Item = (K, S)>,
+    {
+        merge(self, other, policy)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, S, H> Merge<K, S> for HashMap<K, S, H>
+where
+    K: Eq + core::hash::Hash + Display,
+    H: core::hash::BuildHasher,
+{
+    fn merge_from<I>(&mut self, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+    where
+        I: IntoIterator<Item = (K, S)>,
+    {
+        merge(self, other, policy)
+    }
+}
+
+impl<K, S> Merge<K, Entry<S>> for Registry<S>
+where
+    K: Into<String>,
+{
+    fn merge_from<I>(&mut self, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+    where
+        I: IntoIterator<Item = (K, Entry<S>)>,
+    {
+        let other = other.into_iter().map(|(tag, entry)| (tag.into(), entry));
+        merge(&mut self.entries, other, policy)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, S> Merge<K, S> for SharedRegistry<S>
+where
+    K: Into<String>,
+{
+    fn merge_from<I>(&mut self, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+    where
+        I: IntoIterator<Item = (K, S)>,
+    {
+        let seeds = self.seeds.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let other = other
+            .into_iter()
+            .map(|(tag, seed)| (tag.into(), Arc::new(seed)));
+
+        merge(seeds, other, policy)
+    }
+}
+
+
+trait MergeTarget<K, S> {
+    fn contains(&self, key: &K) -> bool;
+    fn insert(&mut self, key: K, seed: S);
+}
+
+impl<K, S> MergeTarget<K, S> for BTreeMap<K, S>
+where
+    K: Ord,
+{
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, seed: S) {
+        BTreeMap::insert(self, key, seed);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, S, H> MergeTarget<K, S> for HashMap<K, S, H>
+where
+    K: Eq + core::hash::Hash,
+    H: core::hash::BuildHasher,
+{
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, seed: S) {
+        HashMap::insert(self, key, seed);
+    }
+}
+
+fn merge<T, K, S, I>(target: &mut T, other: I, policy: Conflict) -> Result<MergeReport, MergeError>
+where
+    T: MergeTarget<K, S>,
+    K: Display,
+    I: IntoIterator<Item = (K, S)>,
+{
+    let mut duplicates = Vec::new();
+
+    match policy {
+        Conflict::Error => {
+            let other: Vec<_> = other.into_iter().collect();
+            let mut seen = BTreeSet::new();
+
+            for (key, _) in &other {
+                let tag = key.to_string();
+
+                if target.contains(key) || seen.contains(&tag) {
+                    duplicates.push(tag);
+                } else {
+                    seen.insert(tag);
+                }
+            }
+
+            if !duplicates.is_empty() {
+                return Err(MergeError { duplicates });
+            }
+
+            for (key, seed) in other {
+                target.insert(key, seed);
+            }
+        },
+        Conflict::KeepFirst => {
+            for (key, seed) in other {
+                if target.contains(&key) {
+                    duplicates.push(key.to_string());
+                } else {
+                    target.insert(key, seed);
+                }
+            }
+        },
+        Conflict::KeepLast => {
+            for (key, seed) in other {
+                if target.contains(&key) {
+                    duplicates.push(key.to_string());
+                }
+                target.insert(key, seed);
+            }
+        },
+    }
+
+    Ok(MergeReport { duplicates })
+}
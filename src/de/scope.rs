@@ -0,0 +1,47 @@
+//! Registries scoped to the current thread.
+//!
+//! `Deserialize` implementations (e.g. for `Box<dyn Trait>`) cannot receive a
+//! registry as argument and thus typically rely on a global registry. This
+//! prevents using different registries, e.g. in tests running in parallel.
+//! Using [`with_registry`], a registry can instead be made available for the
+//! duration of a closure on the current thread. `Deserialize`
+//! implementations, including those of nested values, can then access the
+//! inner-most registry of a specific type via [`with_current_registry`].
+
+use crate::util::scope::{self, Stack};
+
+use core::cell::RefCell;
+
+use alloc::vec::Vec;
+
+
+std::thread_local! {
+    static REGISTRIES: Stack = const { RefCell::new(Vec::new()) };
+}
+
+
+/// Executes the given closure with the given registry in scope.
+///
+/// During execution of the closure, the registry can be accessed on the
+/// current thread via [`with_current_registry`]. Scopes can be nested, in
+/// which case the inner-most registry of the requested type is used.
+pub fn with_registry<R, F, T>(registry: &R, func: F) -> T
+where
+    R: 'static,
+    F: FnOnce() -> T,
+{
+    scope::enter(&REGISTRIES, registry, func)
+}
+
+/// Executes the given closure with the inner-most registry of type `R` that
+/// is in scope on the current thread.
+///
+/// Returns `None` (without executing the closure) if no registry of this type
+/// is in scope.
+pub fn with_current_registry<R, F, T>(func: F) -> Option<T>
+where
+    R: 'static,
+    F: FnOnce(&R) -> T,
+{
+    scope::current(&REGISTRIES, func)
+}
@@ -1,9 +1,14 @@
 //! `DeserializeSeed` implementations for tagged value deserialization and
 //! creation thereof.
 
-use std;
-use std::collections::{BTreeMap, HashMap};
-use std::marker::PhantomData;
+use core;
+use core::marker::PhantomData;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::util::TagString;
 
@@ -32,6 +37,35 @@ pub trait SeedFactory<'de, T> {
         E: serde::de::Error;
 }
 
+/// Returns the error reported by the seed-factories of this crate if they do
+/// not know the given tag.
+pub(crate) fn unknown_tag<E, T>(tag: T) -> E
+where
+    E: serde::de::Error,
+    T: core::fmt::Display,
+{
+    E::custom(format_args!("{}{}`", UNKNOWN_TAG, tag))
+}
+
+/// Returns the error reported by the seed-factories of this crate if they do
+/// not know the given tag, with details on why it is unknown.
+pub(crate) fn unknown_tag_because<E, T, R>(tag: T, reason: R) -> E
+where
+    E: serde::de::Error,
+    T: core::fmt::Display,
+    R: core::fmt::Display,
+{
+    E::custom(format_args!("{}{}` ({})", UNKNOWN_TAG, tag, reason))
+}
+
+/// Returns `true` if the given error message has been created by
+/// [`unknown_tag`] or [`unknown_tag_because`].
+pub(crate) fn is_unknown_tag(msg: &str) -> bool {
+    msg.starts_with(UNKNOWN_TAG)
+}
+
+const UNKNOWN_TAG: &str = "unknown tag `";
+
 
 impl<'de, T, V> SeedFactory<'de, T> for PhantomData<V>
 where
@@ -168,6 +202,90 @@ where
 }
 
 
+/// A function pointer, taking a buffered value of a specific type, that can be
+/// used as `DeserializeSeed`.
+///
+/// This provides a dynamic seed that does not require the `erased` feature,
+/// e.g. for registries of trait objects
+/// (`BTreeMap<&'static str, FnPtrSeed<D, Box<dyn Trait>>>`). The value is
+/// first deserialized into (i.e. buffered as) the type `D`, which is then
+/// handed to the function as deserializer. Thus `D` is typically the generic
+/// value type of a data format, e.g. `serde_json::Value`, which implements
+/// both `Deserialize` and `Deserializer` for any lifetime.
+///
+/// # Note
+///
+/// The function cannot take the deserializer of the data format directly: The
+/// tagging formats hand the value to the seed via
+/// `DeserializeSeed::deserialize`, which is generic over the deserializer, and
+/// the type of the deserializer passed there (e.g. the one provided by
+/// `MapAccess::next_value_seed`) is an implementation detail of the data
+/// format. Without type-erasure, the only way to call a function with a fixed
+/// argument type is thus to buffer the value, which costs an allocation per
+/// value. If this is not acceptable, use the seeds provided with the `erased`
+/// feature (e.g. `BoxFnSeed`) instead.
+///
+/// # Warning
+///
+/// This requires a self-describing data format.
+pub struct FnPtrSeed<D, V>
+where
+    D: serde::Deserializer<'static>,
+{
+    func: fn(D) -> Result<V, D::Error>,
+}
+
+impl<D, V> FnPtrSeed<D, V>
+where
+    D: serde::Deserializer<'static>,
+{
+    /// Creates a new seed from the given function pointer.
+    pub fn new(func: fn(D) -> Result<V, D::Error>) -> Self {
+        FnPtrSeed { func }
+    }
+}
+
+impl<D, V> Clone for FnPtrSeed<D, V>
+where
+    D: serde::Deserializer<'static>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<D, V> Copy for FnPtrSeed<D, V> where D: serde::Deserializer<'static> {}
+
+impl<'de, D, V> serde::de::DeserializeSeed<'de> for FnPtrSeed<D, V>
+where
+    D: serde::Deserializer<'static> + serde::Deserialize<'de>,
+{
+    type Value = V;
+
+    fn deserialize<De>(self, deserializer: De) -> Result<Self::Value, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        let buffered = D::deserialize(deserializer)?;
+        (self.func)(buffered).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<'de, D, V> serde::de::DeserializeSeed<'de> for &FnPtrSeed<D, V>
+where
+    D: serde::Deserializer<'static> + serde::Deserialize<'de>,
+{
+    type Value = V;
+
+    fn deserialize<De>(self, deserializer: De) -> Result<Self::Value, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        (*self).deserialize(deserializer)
+    }
+}
+
+
 impl<'de, V, S> SeedFactory<'de, TagString<'de>> for BTreeMap<&'static str, S>
 where
     S: serde::de::DeserializeSeed<'de, Value = V>,
@@ -179,15 +297,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.remove(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.remove(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'de, V, S, H> SeedFactory<'de, TagString<'de>> for HashMap<&'static str, S, H>
 where
     S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = S;
@@ -196,8 +314,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.remove(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.remove(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -213,15 +330,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.get_mut(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get_mut(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'r, 'de, V, S, H> SeedFactory<'de, TagString<'de>> for &'r mut HashMap<&'static str, S, H>
 where
     &'r mut S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = &'r mut S;
@@ -230,8 +347,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.get_mut(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get_mut(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -247,15 +363,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.get(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'r, 'de, V, S, H> SeedFactory<'de, TagString<'de>> for &'r HashMap<&'static str, S, H>
 where
     &'r S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = &'r S;
@@ -264,8 +380,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.get(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -281,15 +396,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.remove(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.remove(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'de, V, S, H> SeedFactory<'de, TagString<'de>> for HashMap<String, S, H>
 where
     S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = S;
@@ -298,8 +413,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.remove(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.remove(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -315,15 +429,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.get_mut(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get_mut(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'r, 'de, V, S, H> SeedFactory<'de, TagString<'de>> for &'r mut HashMap<String, S, H>
 where
     &'r mut S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = &'r mut S;
@@ -332,8 +446,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.get_mut(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get_mut(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -349,15 +462,15 @@ where
     where
         E: serde::de::Error,
     {
-        self.get(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
+#[cfg(feature = "std")]
 impl<'r, 'de, V, S, H> SeedFactory<'de, TagString<'de>> for &'r HashMap<String, S, H>
 where
     &'r S: serde::de::DeserializeSeed<'de, Value = V>,
-    H: std::hash::BuildHasher,
+    H: core::hash::BuildHasher,
 {
     type Value = V;
     type Seed = &'r S;
@@ -366,8 +479,7 @@ where
     where
         E: serde::de::Error,
     {
-        self.get(tag.as_ref())
-            .ok_or_else(|| serde::de::Error::custom("Unknown tag"))
+        self.get(tag.as_ref()).ok_or_else(|| unknown_tag(tag))
     }
 }
 
@@ -376,6 +488,9 @@ where
 mod erased {
     //! Utilities for trait-objects.
 
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+
     use erased_serde;
     use serde;
 
@@ -492,7 +607,235 @@ mod erased {
             (self.0)(&mut de).map_err(serde::de::Error::custom)
         }
     }
+
+
+    /// A reference-counted (immutable) closure that can be used as
+    /// `DeserializeSeed`.
+    ///
+    /// In contrast to `BoxFnSeed`, this type can be cloned cheaply, as clones
+    /// share the wrapped closure. This allows registries storing seeds of this
+    /// type to be cloned. It requires the wrapped closure to implement `Send`
+    /// and `Sync`, thus seeds can be shared between threads.
+    pub struct ArcFnSeed<V>(
+        Arc<dyn FnSeed<V, Output = Result<V, erased_serde::Error>> + Send + Sync>,
+    );
+
+    impl<V> ArcFnSeed<V> {
+        /// Creates a new reference-counted closure from the given closure.
+        pub fn new<F>(func: F) -> Self
+        where
+            F: FnSeed<V> + Send + Sync + 'static,
+        {
+            ArcFnSeed(Arc::new(func))
+        }
+    }
+
+    impl<V> Clone for ArcFnSeed<V> {
+        fn clone(&self) -> Self {
+            ArcFnSeed(self.0.clone())
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for ArcFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for &ArcFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+
+    /// A boxed (immutable) closure that can be used as `DeserializeSeed`.
+    ///
+    /// In contrast to `BoxFnSeed`, it only requires the wrapped closure to
+    /// implement `Send` instead of `Sync`, which allows registries storing
+    /// seeds of this type to be moved to other threads, but not to be shared
+    /// between them.
+    pub struct SendFnSeed<V>(Box<dyn FnSeed<V, Output = Result<V, erased_serde::Error>> + Send>);
+
+    impl<V> SendFnSeed<V> {
+        /// Creates a new boxed closure from the given closure.
+        pub fn new<F>(func: F) -> Self
+        where
+            F: FnSeed<V> + Send + 'static,
+        {
+            SendFnSeed(Box::new(func))
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for SendFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for &SendFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+
+    /// A boxed (immutable) closure that can be used as `DeserializeSeed`.
+    ///
+    /// In contrast to `BoxFnSeed`, it does not require the wrapped closure to
+    /// implement `Sync` or `Send`, which allows closures capturing thread-local
+    /// state (e.g. an `Rc`) to be used. Registries storing seeds of this type
+    /// can only be used on the thread they have been created on.
+    pub struct LocalFnSeed<V>(Box<dyn FnSeed<V, Output = Result<V, erased_serde::Error>>>);
+
+    impl<V> LocalFnSeed<V> {
+        /// Creates a new boxed closure from the given closure.
+        pub fn new<F>(func: F) -> Self
+        where
+            F: FnSeed<V> + 'static,
+        {
+            LocalFnSeed(Box::new(func))
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for LocalFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+    impl<'de, V> serde::de::DeserializeSeed<'de> for &LocalFnSeed<V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0)(&mut de).map_err(serde::de::Error::custom)
+        }
+    }
+
+    /// A trait alias for (immutable) closures that can be used as
+    /// `DeserializeSeed` with access to a context in combination with
+    /// `BoxFnSeedWithContext`.
+    pub trait FnSeedWithContext<C: ?Sized, V>:
+        for<'de> Fn(
+        &mut C,
+        &mut dyn erased_serde::Deserializer<'de>,
+    ) -> Result<V, erased_serde::Error>
+    {
+    }
+
+    impl<C: ?Sized, V, F> FnSeedWithContext<C, V> for F where
+        F: for<'de> Fn(
+            &mut C,
+            &mut dyn erased_serde::Deserializer<'de>,
+        ) -> Result<V, erased_serde::Error>
+    {
+    }
+
+
+    /// A boxed (immutable) closure that can be used as `DeserializeSeed` once
+    /// it has been provided with a context.
+    ///
+    /// The context (e.g. a string interner or an arena) is passed to the
+    /// closure for each deserialization call, which allows the closure to
+    /// access shared state without resorting to globals. Use
+    /// [`with_context`](BoxFnSeedWithContext::with_context) to create the
+    /// actual `DeserializeSeed` for a single deserialization call. Contexts
+    /// that should only be accessed immutably can be passed by using a shared
+    /// reference as context type, i.e. `BoxFnSeedWithContext<&T, V>`.
+    ///
+    /// It additionally requires the wrapped closure to implement `Sync` which
+    /// allows for easy static type-registry creation.
+    pub struct BoxFnSeedWithContext<C: ?Sized, V>(
+        Box<dyn FnSeedWithContext<C, V, Output = Result<V, erased_serde::Error>> + Sync>,
+    );
+
+    impl<C: ?Sized, V> BoxFnSeedWithContext<C, V> {
+        /// Creates a new boxed closure from the given closure.
+        pub fn new<F>(func: F) -> Self
+        where
+            F: FnSeedWithContext<C, V> + Sync + 'static,
+        {
+            BoxFnSeedWithContext(Box::new(func))
+        }
+
+        /// Creates a `DeserializeSeed` passing the given context to this
+        /// closure.
+        pub fn with_context<'c>(&self, context: &'c mut C) -> SeedWithContext<'_, 'c, C, V> {
+            SeedWithContext {
+                seed: self,
+                context,
+            }
+        }
+    }
+
+
+    /// A `DeserializeSeed` implementation passing a context to a
+    /// `BoxFnSeedWithContext`.
+    ///
+    /// Instances of this type are created by
+    /// [`BoxFnSeedWithContext::with_context`].
+    pub struct SeedWithContext<'s, 'c, C: ?Sized, V> {
+        seed:    &'s BoxFnSeedWithContext<C, V>,
+        context: &'c mut C,
+    }
+
+    impl<'s, 'c, 'de, C: ?Sized, V> serde::de::DeserializeSeed<'de> for SeedWithContext<'s, 'c, C, V> {
+        type Value = V;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let mut de = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.seed.0)(self.context, &mut de).map_err(serde::de::Error::custom)
+        }
+    }
 }
 
 #[cfg(feature = "erased")]
-pub use self::erased::{BoxFnMutSeed, BoxFnSeed, FnMutSeed, FnSeed};
+pub use self::erased::{
+    ArcFnSeed,
+    BoxFnMutSeed,
+    BoxFnSeed,
+    BoxFnSeedWithContext,
+    FnMutSeed,
+    FnSeed,
+    FnSeedWithContext,
+    LocalFnSeed,
+    SeedWithContext,
+    SendFnSeed,
+};
@@ -0,0 +1,145 @@
+//! Deserialization of tagged values serialized as enum variants.
+//!
+//! See [`ser::variant`](crate::ser::variant) for a description of this
+//! tagging format.
+
+use crate::de::seed::SeedFactory;
+use crate::format::Variants;
+use crate::util::TagString;
+
+use core::fmt;
+
+use serde;
+
+
+/// Deserialize a tagged value serialized as newtype variant.
+///
+/// The deserializer controls the underlying data format while the seed-factory
+/// specifies the instructions (depending on the tag) on how the value should be
+/// deserialized. The variant may be identified by either its name or its index
+/// in the given variants, depending on the data format.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub fn deserialize<'de, D, F>(
+    deserializer: D,
+    variants: &Variants,
+    seed_factory: F,
+) -> Result<F::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: SeedFactory<'de, TagString<'de>>,
+{
+    deserializer.deserialize_enum(
+        variants.name(),
+        variants.tags(),
+        Visitor::new(*variants, seed_factory),
+    )
+}
+
+
+/// A visitor that can be used to deserialize a tagged value serialized as
+/// newtype variant.
+///
+/// The [`SeedFactory`] provided to this visitor provides a
+/// `serde::de::DeserializeSeed` implementation depending on the tag, which then
+/// determines how the value is going to be deserialized.
+///
+/// See [`de`](crate::de) for more information on [`SeedFactory`] and
+/// implementations thereof.
+pub struct Visitor<F> {
+    variants:     Variants,
+    seed_factory: F,
+}
+
+impl<F> Visitor<F> {
+    /// Creates a new visitor with the given variants and [`SeedFactory`].
+    pub fn new(variants: Variants, seed_factory: F) -> Self {
+        Visitor {
+            variants,
+            seed_factory,
+        }
+    }
+}
+
+impl<'de, F> serde::de::Visitor<'de> for Visitor<F>
+where
+    F: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = F::Value;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "an enum variant of `{}`", self.variants.name())
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'de>,
+    {
+        use serde::de::VariantAccess;
+
+        let (tag, variant) = data.variant_seed(TagSeed(self.variants))?;
+        variant.newtype_variant_seed(self.seed_factory.seed(TagString::Borrowed(tag))?)
+    }
+}
+
+
+/// A seed deserializing a variant identifier (i.e. name or index) to its tag.
+struct TagSeed(Variants);
+
+impl<'de> serde::de::DeserializeSeed<'de> for TagSeed {
+    type Value = &'static str;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for TagSeed {
+    type Value = &'static str;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a variant name or index")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let index = u32::try_from(value).ok();
+
+        match index.and_then(|index| self.0.tag(index)) {
+            Some(tag) => Ok(tag),
+            None => Err(E::invalid_value(
+                serde::de::Unexpected::Unsigned(value),
+                &"a valid variant index",
+            )),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match self.0.index(value) {
+            Some((_, tag)) => Ok(tag),
+            None => Err(E::unknown_variant(value, self.0.tags())),
+        }
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match core::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(E::invalid_value(
+                serde::de::Unexpected::Bytes(value),
+                &"a variant name",
+            )),
+        }
+    }
+}
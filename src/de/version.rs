@@ -0,0 +1,338 @@
+//! Deserialization of values with versioned tags.
+//!
+//! Types that are persisted (e.g. as trait-objects) tend to evolve over time,
+//! which means that old documents may contain payloads of an outdated version
+//! of the type. This module provides the [`Versioned`] seed-factory, which
+//! understands tags of the form `<name>@<version>` (e.g. `"A@1"`), and a
+//! [`Migration`] describing how to upgrade outdated payloads of a type, one
+//! version at a time, to the current version of that type.
+//!
+//! Outdated payloads are deserialized into a [`Value`], which is then passed
+//! through the chain of registered upgrade functions (`v1 -> v2 -> v3`). The
+//! resulting value is finally handed to the seed provided by the wrapped
+//! seed-factory for the current type. Payloads of the current version (as well
+//! as payloads with a tag that does not specify any version) are handed to the
+//! seed directly.
+//!
+//! As [`Versioned`] is a [`SeedFactory`], it can be used with all tagging
+//! formats provided in [`de`](crate::de).
+//!
+//! # Warning
+//!
+//! Migration of outdated payloads requires a self-describing data format.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde;
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! # #[cfg(not(feature = "erased"))]
+//! # fn main() {}
+//! #
+//! # #[cfg(feature = "erased")]
+//! # fn main() {
+//! use serde::Deserialize;
+//! use serde_tagged::de::BoxFnSeed;
+//! use serde_tagged::de::version::{Migration, Versioned};
+//! use serde_tagged::util::Value;
+//!
+//! use std::collections::BTreeMap;
+//!
+//! // A type `A`, which stored a plain integer in version 1 and has since been
+//! // changed to store a sequence of integers.
+//! fn deserialize_a<'de>(
+//!     de: &mut dyn erased_serde::Deserializer<'de>,
+//! ) -> Result<Vec<u64>, erased_serde::Error> {
+//!     Vec::deserialize(de)
+//! }
+//!
+//! let mut seeds = BTreeMap::new();
+//! seeds.insert("A", BoxFnSeed::new(deserialize_a));
+//!
+//! let mut registry = Versioned::new(seeds);
+//! registry.register("A", Migration::new(2).upgrade(1, |v| Ok(Value::Seq(vec![v]))));
+//!
+//! let json = r#"{ "A@1": 42 }"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let value = serde_tagged::de::external::deserialize(&mut de, &registry).unwrap();
+//!
+//! assert_eq!(value, vec![42]);
+//! # }
+//! ```
+
+
+use crate::de::seed::SeedFactory;
+use crate::util::de::content::ContentDeserializer;
+use crate::util::{TagString, Value};
+
+use core;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+
+use serde;
+
+
+/// The separator between name and version of a versioned tag.
+pub const SEPARATOR: char = '@';
+
+
+/// A tag consisting of a name and an (optional) version.
+///
+/// A versioned tag is represented as string of the form `<name>@<version>`,
+/// e.g. `"A@3"`. If the version is not specified, the tag is represented by
+/// its name only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionedTag<'a> {
+    /// The name of the tag.
+    pub name: TagString<'a>,
+
+    /// The version of the tag, if specified.
+    pub version: Option<u32>,
+}
+
+impl<'a> VersionedTag<'a> {
+    /// Creates a new versioned tag with the given name and version.
+    pub fn new<N>(name: N, version: u32) -> Self
+    where
+        N: Into<TagString<'a>>,
+    {
+        VersionedTag {
+            name:    name.into(),
+            version: Some(version),
+        }
+    }
+
+    /// Parses the given string-based tag into name and version.
+    ///
+    /// The version is the number following the last separator, written in
+    /// ASCII digits without sign or leading zeros (i.e. as produced by the
+    /// `Display` implementation). If there is no separator or the part
+    /// following it is not such a number, the whole tag is treated as name of
+    /// an unversioned tag, i.e. names may contain the separator.
+    ///
+    /// This does not allocate, the name of the returned tag will be borrowed
+    /// if the given tag has been borrowed and owned otherwise.
+    pub fn parse(tag: TagString<'a>) -> Self {
+        let split = tag.rfind(SEPARATOR).and_then(|pos| {
+            let version = &tag[pos + SEPARATOR.len_utf8()..];
+
+            let digits = !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit());
+            if !digits || (version.len() > 1 && version.starts_with('0')) {
+                return None;
+            }
+
+            Some((pos, version.parse().ok()?))
+        });
+
+        match split {
+            Some((pos, version)) => VersionedTag {
+                name:    tag.into_slice(0, pos),
+                version: Some(version),
+            },
+            None => VersionedTag {
+                name:    tag,
+                version: None,
+            },
+        }
+    }
+}
+
+impl<'a> core::fmt::Display for VersionedTag<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "{}{}{}", self.name, SEPARATOR, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl<'a> serde::Serialize for VersionedTag<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+
+type BoxFnUpgrade = Box<dyn for<'de> Fn(Value<'de>) -> Result<Value<'de>, String> + Sync>;
+
+/// A chain of upgrade functions for a single type.
+///
+/// Each upgrade function transforms a payload of one version to the next
+/// version, i.e. the function registered for version `1` transforms a payload
+/// of version `1` to a payload of version `2`.
+pub struct Migration {
+    current:  u32,
+    upgrades: BTreeMap<u32, BoxFnUpgrade>,
+}
+
+impl Migration {
+    /// Creates a new migration without any upgrade functions for a type with
+    /// the given current version.
+    pub fn new(current: u32) -> Self {
+        Migration {
+            current,
+            upgrades: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the upgrade function transforming a payload of version `from` to a
+    /// payload of version `from + 1`.
+    ///
+    /// The function is required to implement `Sync` which allows for easy
+    /// static registry creation.
+    pub fn upgrade<F>(mut self, from: u32, func: F) -> Self
+    where
+        F: for<'de> Fn(Value<'de>) -> Result<Value<'de>, String> + Sync + 'static,
+    {
+        self.upgrades.insert(from, Box::new(func));
+        self
+    }
+
+    /// Returns the current version of the type described by this migration.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Applies all upgrade functions required to transform the given payload
+    /// of the given version into a payload of the current version.
+    pub fn migrate<'de>(&self, version: u32, value: Value<'de>) -> Result<Value<'de>, String> {
+        if version > self.current {
+            return Err(format!(
+                "unsupported version {}, the current version is {}",
+                version, self.current
+            ));
+        }
+
+        (version..self.current).try_fold(value, |value, version| {
+            match self.upgrades.get(&version) {
+                Some(upgrade) => upgrade(value),
+                None => Err(format!("no upgrade from version {} registered", version)),
+            }
+        })
+    }
+}
+
+
+/// A [`SeedFactory`] implementation for versioned tags.
+///
+/// This factory parses string-based tags into a [`VersionedTag`] and uses the
+/// wrapped factory to look up the seed for the name of the tag. If the tag
+/// specifies an outdated version of a type, for which a [`Migration`] has been
+/// registered, the payload will be upgraded to the current version before it
+/// is handed to the seed.
+///
+/// Tags of types without a registered migration are forwarded to the wrapped
+/// factory regardless of their version.
+pub struct Versioned<F> {
+    factory:    F,
+    migrations: BTreeMap<String, Migration>,
+}
+
+impl<F> Versioned<F> {
+    /// Creates a new versioned [`SeedFactory`] wrapping the given factory.
+    pub fn new(factory: F) -> Self {
+        Versioned {
+            factory,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the migration for the type with the given name, returning
+    /// the previously registered migration of that type (if any).
+    pub fn register<N>(&mut self, name: N, migration: Migration) -> Option<Migration>
+    where
+        N: Into<String>,
+    {
+        self.migrations.insert(name.into(), migration)
+    }
+
+    /// Returns the migration registered for the type with the given name.
+    pub fn migration(&self, name: &str) -> Option<&Migration> {
+        self.migrations.get(name)
+    }
+
+    /// Returns a reference to the wrapped [`SeedFactory`].
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+}
+
+impl<'r, 'de, F> SeedFactory<'de, TagString<'de>> for &'r Versioned<F>
+where
+    &'r F: SeedFactory<'de, TagString<'de>>,
+{
+    type Value = <&'r F as SeedFactory<'de, TagString<'de>>>::Value;
+    type Seed = VersionedSeed<'r, <&'r F as SeedFactory<'de, TagString<'de>>>::Seed>;
+
+    fn seed<E>(self, tag: TagString<'de>) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        let tag = VersionedTag::parse(tag);
+
+        let migration = match (self.migrations.get(&*tag.name), tag.version) {
+            (Some(migration), Some(version)) if version > migration.current => {
+                return Err(serde::de::Error::custom(format_args!(
+                    "unsupported version {} of `{}`, the current version is {}",
+                    version, tag.name, migration.current
+                )));
+            },
+            (Some(migration), Some(version)) if version < migration.current => {
+                Some((migration, version))
+            },
+            _ => None,
+        };
+
+        Ok(VersionedSeed {
+            seed: self.factory.seed(tag.name)?,
+            migration,
+        })
+    }
+}
+
+
+/// A `DeserializeSeed` implementation that upgrades outdated payloads before
+/// deserializing them with the wrapped seed.
+///
+/// Instances of this type are created by the [`SeedFactory`] implementation
+/// of [`Versioned`].
+pub struct VersionedSeed<'r, S> {
+    seed:      S,
+    migration: Option<(&'r Migration, u32)>,
+}
+
+impl<'r, 'de, S> serde::de::DeserializeSeed<'de> for VersionedSeed<'r, S>
+where
+    S: serde::de::DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+
+        match self.migration {
+            Some((migration, version)) => {
+                let value = Value::deserialize(deserializer)?;
+                let value = migration
+                    .migrate(version, value)
+                    .map_err(D::Error::custom)?;
+
+                self.seed.deserialize(ContentDeserializer::new(value))
+            },
+            None => self.seed.deserialize(deserializer),
+        }
+    }
+}
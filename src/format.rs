@@ -0,0 +1,813 @@
+//! Descriptions of the supported tagging formats.
+
+use crate::de::SeedFactory;
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use serde;
+
+
+/// A description of a tagging format, including its parameters.
+///
+/// Each variant corresponds to one of the formats provided in [`ser`](crate::ser)
+/// and [`de`](crate::de). Values can be serialized and deserialized in a
+/// format selected at runtime via [`ser::dynamic`](crate::ser::dynamic) and
+/// [`de::dynamic`](crate::de::dynamic).
+///
+/// Formats can be serialized and deserialized themselves, e.g. to be read
+/// from a configuration file. Unit variants are represented by their name in
+/// snake case (e.g. `"external"`), the others as struct variants with their
+/// parameters as fields (e.g. `{ "internal": { "tag_key": "type" } }`).
+///
+/// # Owned names and keys
+///
+/// Names and keys are either borrowed (i.e. `'static`) or owned, e.g. if the
+/// format has been deserialized. The representation of values does not
+/// depend on this, as serde requires names of structs and their fields to be
+/// `'static`:
+///
+/// - When dispatched at runtime, [`Internal`](TagFormat::Internal) always
+///   embeds the tag into structs serialized as maps. For self-describing data
+///   formats, e.g. JSON, this does not make any difference to
+///   [`ser::internal`](crate::ser::internal).
+/// - [`AdjStruct`](TagFormat::AdjStruct) requires its name and keys to be
+///   borrowed, serialization and deserialization fail otherwise. Use
+///   [`AdjMap`](TagFormat::AdjMap) for keys only known at runtime.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TagFormat {
+    /// Externally tagged values, see [`ser::external`](crate::ser::external).
+    External,
+
+    /// Internally tagged values, see [`ser::internal`](crate::ser::internal).
+    Internal {
+        /// The key under which the tag is stored.
+        tag_key: Cow<'static, str>,
+    },
+
+    /// Adjacently tagged values using tuples, see
+    /// [`ser::adj::tuple`](crate::ser::adj::tuple).
+    AdjTuple,
+
+    /// Adjacently tagged values using maps, see
+    /// [`ser::adj::map`](crate::ser::adj::map).
+    AdjMap {
+        /// The key under which the tag is stored.
+        tag_key: Cow<'static, str>,
+
+        /// The key under which the value is stored.
+        value_key: Cow<'static, str>,
+    },
+
+    /// Adjacently tagged values using structs, see
+    /// [`ser::adj::struc`](crate::ser::adj::struc).
+    AdjStruct {
+        /// The name of the struct.
+        name: Cow<'static, str>,
+
+        /// The name of the field containing the tag.
+        tag_key: Cow<'static, str>,
+
+        /// The name of the field containing the value.
+        value_key: Cow<'static, str>,
+    },
+}
+
+impl TagFormat {
+    /// Creates an [`Internal`](TagFormat::Internal) format with the given tag
+    /// key.
+    pub const fn internal(tag_key: &'static str) -> Self {
+        TagFormat::Internal {
+            tag_key: Cow::Borrowed(tag_key),
+        }
+    }
+
+    /// Creates an [`AdjMap`](TagFormat::AdjMap) format with the given keys.
+    pub const fn adj_map(tag_key: &'static str, value_key: &'static str) -> Self {
+        TagFormat::AdjMap {
+            tag_key:   Cow::Borrowed(tag_key),
+            value_key: Cow::Borrowed(value_key),
+        }
+    }
+
+    /// Creates an [`AdjStruct`](TagFormat::AdjStruct) format with the given
+    /// struct name and keys.
+    pub const fn adj_struct(
+        name: &'static str,
+        tag_key: &'static str,
+        value_key: &'static str,
+    ) -> Self {
+        TagFormat::AdjStruct {
+            name:      Cow::Borrowed(name),
+            tag_key:   Cow::Borrowed(tag_key),
+            value_key: Cow::Borrowed(value_key),
+        }
+    }
+}
+
+/// The error reported by dynamic dispatch for an `AdjStruct` format with owned
+/// names or keys.
+pub(crate) const OWNED_ADJ_STRUCT: &str =
+    "the name and keys of `TagFormat::AdjStruct` must be static, use `TagFormat::AdjMap` instead";
+
+const VARIANTS: &[&str] = &["external", "internal", "adj_tuple", "adj_map", "adj_struct"];
+
+const FIELDS: &[&str] = &["name", "tag_key", "value_key"];
+
+impl serde::Serialize for TagFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStructVariant;
+
+        match *self {
+            TagFormat::External => serializer.serialize_unit_variant("TagFormat", 0, "external"),
+            TagFormat::Internal { ref tag_key } => {
+                let mut state =
+                    serializer.serialize_struct_variant("TagFormat", 1, "internal", 1)?;
+                state.serialize_field("tag_key", tag_key)?;
+                state.end()
+            },
+            TagFormat::AdjTuple => serializer.serialize_unit_variant("TagFormat", 2, "adj_tuple"),
+            TagFormat::AdjMap {
+                ref tag_key,
+                ref value_key,
+            } => {
+                let mut state =
+                    serializer.serialize_struct_variant("TagFormat", 3, "adj_map", 2)?;
+                state.serialize_field("tag_key", tag_key)?;
+                state.serialize_field("value_key", value_key)?;
+                state.end()
+            },
+            TagFormat::AdjStruct {
+                ref name,
+                ref tag_key,
+                ref value_key,
+            } => {
+                let mut state =
+                    serializer.serialize_struct_variant("TagFormat", 4, "adj_struct", 3)?;
+                state.serialize_field("name", name)?;
+                state.serialize_field("tag_key", tag_key)?;
+                state.serialize_field("value_key", value_key)?;
+                state.end()
+            },
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TagFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_enum("TagFormat", VARIANTS, FormatVisitor)
+    }
+}
+
+
+/// The variants of [`TagFormat`], used for deserialization.
+#[derive(Clone, Copy)]
+enum Variant {
+    External,
+    Internal,
+    AdjTuple,
+    AdjMap,
+    AdjStruct,
+}
+
+impl Variant {
+    /// The fields of the variant, in order of serialization.
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            Variant::External | Variant::AdjTuple => &[],
+            Variant::Internal => &FIELDS[1..2],
+            Variant::AdjMap => &FIELDS[1..],
+            Variant::AdjStruct => FIELDS,
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Variant {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(VariantVisitor)
+    }
+}
+
+struct VariantVisitor;
+
+impl<'de> serde::de::Visitor<'de> for VariantVisitor {
+    type Value = Variant;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a variant name or index")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match value {
+            0 => Ok(Variant::External),
+            1 => Ok(Variant::Internal),
+            2 => Ok(Variant::AdjTuple),
+            3 => Ok(Variant::AdjMap),
+            4 => Ok(Variant::AdjStruct),
+            _ => Err(E::invalid_value(
+                serde::de::Unexpected::Unsigned(value),
+                &"a variant index 0 <= i < 5",
+            )),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match value {
+            "external" => Ok(Variant::External),
+            "internal" => Ok(Variant::Internal),
+            "adj_tuple" => Ok(Variant::AdjTuple),
+            "adj_map" => Ok(Variant::AdjMap),
+            "adj_struct" => Ok(Variant::AdjStruct),
+            _ => Err(E::unknown_variant(value, VARIANTS)),
+        }
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match core::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Bytes(value), &self)),
+        }
+    }
+}
+
+
+/// The fields of [`TagFormat`] variants, used for deserialization.
+#[derive(Clone, Copy)]
+enum Field {
+    Name,
+    TagKey,
+    ValueKey,
+}
+
+impl<'de> serde::Deserialize<'de> for Field {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl<'de> serde::de::Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a field name")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match value {
+            "name" => Ok(Field::Name),
+            "tag_key" => Ok(Field::TagKey),
+            "value_key" => Ok(Field::ValueKey),
+            _ => Err(E::unknown_field(value, FIELDS)),
+        }
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match core::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Bytes(value), &self)),
+        }
+    }
+}
+
+
+struct FormatVisitor;
+
+impl<'de> serde::de::Visitor<'de> for FormatVisitor {
+    type Value = TagFormat;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "a tagging format")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'de>,
+    {
+        use serde::de::VariantAccess;
+
+        let (variant, access) = data.variant::<Variant>()?;
+
+        match variant {
+            Variant::External => access.unit_variant().map(|_| TagFormat::External),
+            Variant::AdjTuple => access.unit_variant().map(|_| TagFormat::AdjTuple),
+            _ => access.struct_variant(variant.fields(), FieldsVisitor(variant)),
+        }
+    }
+}
+
+
+/// A visitor for the fields of the struct variants of [`TagFormat`].
+struct FieldsVisitor(Variant);
+
+impl FieldsVisitor {
+    fn build<E>(self, mut values: [Option<String>; 3]) -> Result<TagFormat, E>
+    where
+        E: serde::de::Error,
+    {
+        let mut take = |field: Field| {
+            let name = FIELDS[field as usize];
+
+            match values[field as usize].take() {
+                Some(value) => Ok(Cow::Owned(value)),
+                None => Err(E::missing_field(name)),
+            }
+        };
+
+        match self.0 {
+            Variant::Internal => Ok(TagFormat::Internal {
+                tag_key: take(Field::TagKey)?,
+            }),
+            Variant::AdjMap => Ok(TagFormat::AdjMap {
+                tag_key:   take(Field::TagKey)?,
+                value_key: take(Field::ValueKey)?,
+            }),
+            Variant::AdjStruct => Ok(TagFormat::AdjStruct {
+                name:      take(Field::Name)?,
+                tag_key:   take(Field::TagKey)?,
+                value_key: take(Field::ValueKey)?,
+            }),
+            Variant::External | Variant::AdjTuple => unreachable!(),
+        }
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for FieldsVisitor {
+    type Value = TagFormat;
+
+    fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "the parameters of a tagging format")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut values = [None, None, None];
+        let fields = self.0.fields();
+
+        for (i, field) in fields.iter().enumerate() {
+            let slot = FIELDS.iter().position(|f| f == field).unwrap();
+
+            match seq.next_element()? {
+                Some(value) => values[slot] = Some(value),
+                None => return Err(A::Error::invalid_length(i, &self)),
+            }
+        }
+
+        self.build(values)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut values = [None, None, None];
+        let fields = self.0.fields();
+
+        while let Some(field) = map.next_key::<Field>()? {
+            let name = FIELDS[field as usize];
+
+            if !fields.contains(&name) {
+                return Err(A::Error::unknown_field(name, fields));
+            }
+            if values[field as usize].is_some() {
+                return Err(A::Error::duplicate_field(name));
+            }
+
+            values[field as usize] = Some(map.next_value()?);
+        }
+
+        self.build(values)
+    }
+}
+
+
+/// A type providing the keys (and the struct name) used by the
+/// [`Internal`], [`AdjMap`] and [`AdjStruct`] formats.
+///
+/// All keys have default values, see [`DefaultKeys`].
+pub trait TagKeys {
+    /// The key under which the tag is stored.
+    const TAG_KEY: &'static str = "type";
+
+    /// The key under which the value is stored.
+    const VALUE_KEY: &'static str = "value";
+
+    /// The name of the struct used by the [`AdjStruct`] format.
+    const NAME: &'static str = "Tagged";
+}
+
+
+/// The default keys, i.e. `"type"` and `"value"` as tag and value key and
+/// `"Tagged"` as struct name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DefaultKeys;
+
+impl TagKeys for DefaultKeys {}
+
+
+/// A tagging format known at compile time.
+///
+/// This trait is implemented by the zero-sized marker types of this module,
+/// one for each format in [`ser`](crate::ser) and [`de`](crate::de) described
+/// by [`TagFormat`]. The keys of the [`Internal`], [`AdjMap`] and
+/// [`AdjStruct`] formats are provided by a type implementing [`TagKeys`].
+/// This allows for code generic over the tagging format, e.g. the
+/// [`Tagged`](crate::tagged::Tagged) wrapper type.
+///
+/// # Example
+///
+/// ```
+/// # extern crate serde_json;
+/// # extern crate serde_tagged;
+/// #
+/// use serde_tagged::format::{AdjMap, Format, TagFormat, TagKeys};
+///
+/// struct Keys;
+///
+/// impl TagKeys for Keys {
+///     const TAG_KEY: &'static str = "kind";
+/// }
+///
+/// # fn main() {
+/// assert_eq!(AdjMap::<Keys>::TAG_KEY, Some("kind"));
+/// assert_eq!(
+///     AdjMap::<Keys>::FORMAT,
+///     TagFormat::adj_map("kind", "value")
+/// );
+///
+/// let mut out = Vec::new();
+/// let mut serializer = serde_json::Serializer::new(&mut out);
+/// AdjMap::<Keys>::serialize(&mut serializer, "foo", &42).unwrap();
+///
+/// assert_eq!(out, br#"{"kind":"foo","value":42}"#);
+/// # }
+/// ```
+pub trait Format {
+    /// The description of this format, including its parameters.
+    const FORMAT: TagFormat;
+
+    /// The key under which the tag is stored, if used by this format.
+    const TAG_KEY: Option<&'static str> = None;
+
+    /// The key under which the value is stored, if used by this format.
+    const VALUE_KEY: Option<&'static str> = None;
+
+    /// The struct name, if used by this format.
+    const NAME: Option<&'static str> = None;
+
+    /// Serializes the specified tag and value in this format.
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized;
+
+    /// Deserialize a tagged value in this format.
+    fn deserialize<'de, T, D, F>(deserializer: D, seed_factory: F) -> Result<F::Value, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, T>,
+    {
+        Self::deserialize_seed(deserializer, seed_factory, PhantomData::<T>)
+    }
+
+    /// Deserialize a tagged value in this format, using the given
+    /// `DeserializeSeed` for the tag.
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>;
+}
+
+
+/// The [externally tagged](crate::ser::external) format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct External;
+
+impl Format for External {
+    const FORMAT: TagFormat = TagFormat::External;
+
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::external::serialize(serializer, tag, value)
+    }
+
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>,
+    {
+        crate::de::external::deserialize_seed(deserializer, seed_factory, tag_seed)
+    }
+}
+
+
+/// The [tuple-based adjacently tagged](crate::ser::adj::tuple) format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AdjTuple;
+
+impl Format for AdjTuple {
+    const FORMAT: TagFormat = TagFormat::AdjTuple;
+
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::adj::tuple::serialize(serializer, tag, value)
+    }
+
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>,
+    {
+        crate::de::adj::tuple::deserialize_seed(deserializer, seed_factory, tag_seed)
+    }
+}
+
+
+/// Implements the standard traits for a marker type with keys, without
+/// requiring any bounds on the keys.
+macro_rules! keyed_marker {
+    ($name:ident) => {
+        impl<K> Clone for $name<K> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<K> Copy for $name<K> {}
+
+        impl<K> Default for $name<K> {
+            fn default() -> Self {
+                $name(PhantomData)
+            }
+        }
+
+        impl<K> core::fmt::Debug for $name<K> {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+    };
+}
+
+
+/// The [internally tagged](crate::ser::internal) format, using the tag key
+/// specified by `K`.
+pub struct Internal<K = DefaultKeys>(PhantomData<fn() -> K>);
+
+keyed_marker!(Internal);
+
+impl<K: TagKeys> Format for Internal<K> {
+    const FORMAT: TagFormat = TagFormat::internal(K::TAG_KEY);
+    const TAG_KEY: Option<&'static str> = Some(K::TAG_KEY);
+
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::internal::serialize(serializer, K::TAG_KEY, tag, value)
+    }
+
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>,
+    {
+        crate::de::internal::deserialize_seed(deserializer, K::TAG_KEY, seed_factory, tag_seed)
+    }
+}
+
+
+/// The [map-based adjacently tagged](crate::ser::adj::map) format, using the
+/// keys specified by `K`.
+pub struct AdjMap<K = DefaultKeys>(PhantomData<fn() -> K>);
+
+keyed_marker!(AdjMap);
+
+impl<K: TagKeys> Format for AdjMap<K> {
+    const FORMAT: TagFormat = TagFormat::adj_map(K::TAG_KEY, K::VALUE_KEY);
+    const TAG_KEY: Option<&'static str> = Some(K::TAG_KEY);
+    const VALUE_KEY: Option<&'static str> = Some(K::VALUE_KEY);
+
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::adj::map::serialize(serializer, K::TAG_KEY, tag, K::VALUE_KEY, value)
+    }
+
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>,
+    {
+        crate::de::adj::map::deserialize_seed::<String, str, _, _, _>(
+            deserializer,
+            K::TAG_KEY,
+            K::VALUE_KEY,
+            seed_factory,
+            tag_seed,
+        )
+    }
+}
+
+
+/// The [struct-based adjacently tagged](crate::ser::adj::struc) format, using
+/// the name and keys specified by `K`.
+pub struct AdjStruct<K = DefaultKeys>(PhantomData<fn() -> K>);
+
+keyed_marker!(AdjStruct);
+
+impl<K: TagKeys> Format for AdjStruct<K> {
+    const FORMAT: TagFormat = TagFormat::adj_struct(K::NAME, K::TAG_KEY, K::VALUE_KEY);
+    const TAG_KEY: Option<&'static str> = Some(K::TAG_KEY);
+    const VALUE_KEY: Option<&'static str> = Some(K::VALUE_KEY);
+    const NAME: Option<&'static str> = Some(K::NAME);
+
+    fn serialize<S, T, V>(serializer: S, tag: &T, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize + ?Sized,
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::adj::struc::serialize(serializer, K::NAME, K::TAG_KEY, tag, K::VALUE_KEY, value)
+    }
+
+    fn deserialize_seed<'de, D, F, S>(
+        deserializer: D,
+        seed_factory: F,
+        tag_seed: S,
+    ) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, S::Value>,
+        S: serde::de::DeserializeSeed<'de>,
+    {
+        crate::de::adj::struc::deserialize_seed(
+            deserializer,
+            K::NAME,
+            K::TAG_KEY,
+            K::VALUE_KEY,
+            seed_factory,
+            tag_seed,
+        )
+    }
+}
+
+
+/// The variants of the [`ser::variant`](crate::ser::variant) format, i.e. the
+/// enum name and the list of tags, assigning a stable index to each tag.
+///
+/// The index of a tag is its position in the list. Data formats encoding
+/// enum variants by index (e.g. bincode or postcard) rely on these indices,
+/// thus new tags should only ever be appended to the list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Variants {
+    name: &'static str,
+    tags: &'static [&'static str],
+}
+
+impl Variants {
+    /// Creates a new set of variants with the given enum name and tags.
+    pub const fn new(name: &'static str, tags: &'static [&'static str]) -> Self {
+        Variants { name, tags }
+    }
+
+    /// Returns the name of the enum.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the list of tags.
+    pub fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    /// Returns the index and the static representation of the given tag, or
+    /// `None` if the tag is unknown.
+    pub fn index(&self, tag: &str) -> Option<(u32, &'static str)> {
+        self.tags
+            .iter()
+            .position(|t| *t == tag)
+            .map(|i| (i as u32, self.tags[i]))
+    }
+
+    /// Returns the tag with the given index, or `None` if the index is out of
+    /// range.
+    pub fn tag(&self, index: u32) -> Option<&'static str> {
+        self.tags.get(index as usize).copied()
+    }
+}
+
+
+/// An assignment of numeric ids to string tags, used by
+/// [`ser::readable`](crate::ser::readable) and
+/// [`de::readable`](crate::de::readable).
+///
+/// Human-readable data formats store the string tag, all others its id. Ids
+/// are stored on the wire, thus they should never be reassigned once used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TagIds {
+    entries: &'static [(&'static str, u64)],
+}
+
+impl TagIds {
+    /// Creates a new assignment from the given pairs of tag and id.
+    pub const fn new(entries: &'static [(&'static str, u64)]) -> Self {
+        TagIds { entries }
+    }
+
+    /// Returns the pairs of tag and id.
+    pub fn entries(&self) -> &'static [(&'static str, u64)] {
+        self.entries
+    }
+
+    /// Returns the id and the static representation of the given tag, or
+    /// `None` if the tag is unknown.
+    pub fn id(&self, tag: &str) -> Option<(u64, &'static str)> {
+        self.entries
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|&(t, id)| (id, t))
+    }
+
+    /// Returns the tag with the given id, or `None` if the id is unknown.
+    pub fn tag(&self, id: u64) -> Option<&'static str> {
+        self.entries.iter().find(|(_, i)| *i == id).map(|&(t, _)| t)
+    }
+}
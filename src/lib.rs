@@ -29,6 +29,10 @@
 //!   `{ <tag-key> => <tag>, <value-key> => <value> }`
 //! - [adjacently tagged using structs](crate::ser::adj::struc), as in
 //!   `{ <tag-key>: <tag>, <value-key>: <value> }`
+//! - [as enum variant](crate::ser::variant), using the native enum
+//!   representation of the data format
+//! - [natively tagged](crate::ser::native), using the native tag syntax of
+//!   data formats supporting it (falling back to tuples otherwise)
 //!
 //! ## A quick overview
 //!
@@ -56,11 +60,37 @@
 //! variants of it) are provided in the respective format-modules. Have a look
 //! the respective function documentation for more details.
 //!
+//! ### Wrapper types
+//!
+//! For tags and values of known type, the [`tagged`] module provides wrapper
+//! types for each tagging-format, implementing both `Serialize` and
+//! `Deserialize`. These can be used directly in derived structs and
+//! containers.
+//!
+//! ### Nominal tags
+//!
+//! Instead of specifying tags explicitly, [`ser::nominal`] uses the type name
+//! passed to the serializer (e.g. as set via `#[serde(rename = "...")]`) as
+//! tag in any of the formats above. Registries for deserialization can be
+//! keyed by the same names, as obtained via [`de::nominal::name`].
+//!
+//! ### Human-readable tags
+//!
+//! The tag representation in [`ser::readable`] and [`de::readable`] stores
+//! string tags in human-readable data formats and numeric ids in all others,
+//! and can be used with any of the formats above.
+//!
 //! ## Examples
 //!
 //! For some examples have a look at the examples directory in the repository.
 
 #![allow(clippy::redundant_field_names)]
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 extern crate serde;
@@ -70,5 +100,9 @@ extern crate erased_serde;
 
 
 pub mod de;
+pub mod format;
+pub mod schema;
 pub mod ser;
+pub mod tagged;
 pub mod util;
+pub mod with;
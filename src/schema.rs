@@ -0,0 +1,335 @@
+//! Schema descriptions of tagged values.
+//!
+//! Consumers of tagged values that are not written in Rust (e.g. clients of a
+//! web-service) often rely on a schema describing these values. This module
+//! provides a [`Schema`] model for the discriminated union described by a set
+//! of tags and the tagging format used for each of them, where the schema of
+//! the payload of each tag is provided by the user. Serializing a [`Schema`]
+//! (e.g. using `serde_json`) produces a [JSON Schema][json-schema] (draft
+//! 2020-12) compatible description of the union in form of a `oneOf` block.
+//!
+//! Creating the schema from a [`Registry`] (see [`Schema::from_registry`])
+//! ensures that the schema always covers exactly the registered types, each in
+//! the tagging format it has been registered with.
+//!
+//! # Internal tagging
+//!
+//! With [internal tagging](TagFormat::Internal), the tag is embedded into the
+//! payload, which thus has to be an object. The schema of the payload is
+//! combined with the tag property via `allOf`, i.e. a variant with a payload
+//! schema not describing an object cannot be satisfied by any value.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::format::TagFormat;
+//! use serde_tagged::schema::Schema;
+//!
+//! # fn main() {
+//! let format = TagFormat::internal("type");
+//!
+//! let schema = Schema::new()
+//!     .variant("A", format.clone(), serde_json::json!({ "type": "object" }))
+//!     .variant("B", format, serde_json::json!({ "type": "object" }));
+//!
+//! let json = serde_json::to_value(&schema).unwrap();
+//!
+//! assert_eq!(json["oneOf"][0]["allOf"][1]["properties"]["type"]["const"], "A");
+//! # }
+//! ```
+//!
+//! [json-schema]: https://json-schema.org/
+
+use crate::de::registry::{Entry, Registry};
+use crate::format::TagFormat;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+
+/// A schema description of a discriminated union of tagged values.
+///
+/// The schema consists of a list of variants, each consisting of a tag, the
+/// format in which values with that tag are tagged, and the schema of the
+/// payload associated with that tag. The payload schema can be any
+/// serializable type, e.g. a `serde_json::Value` containing a JSON Schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema<P> {
+    variants: Vec<Variant<P>>,
+}
+
+impl<P> Schema<P> {
+    /// Creates a new schema without any variants.
+    pub fn new() -> Self {
+        Schema {
+            variants: Vec::new(),
+        }
+    }
+
+    /// Creates a new schema containing a variant for each tag registered in
+    /// the given registry, using the tagging format of the respective entry.
+    ///
+    /// The payload schema of each variant is provided by the given function,
+    /// based on the tag and its registry entry.
+    pub fn from_registry<S, F>(registry: &Registry<S>, mut payload: F) -> Self
+    where
+        F: FnMut(&str, &Entry<S>) -> P,
+    {
+        let variants = registry
+            .entries()
+            .map(|(tag, entry)| Variant::new(tag, entry.format().clone(), payload(tag, entry)))
+            .collect();
+
+        Schema { variants }
+    }
+
+    /// Adds a variant with the given tag, tagging format, and payload schema.
+    pub fn variant<T>(mut self, tag: T, format: TagFormat, payload: P) -> Self
+    where
+        T: Into<String>,
+    {
+        self.variants.push(Variant::new(tag, format, payload));
+        self
+    }
+
+    /// Returns the variants of this schema.
+    pub fn variants(&self) -> &[Variant<P>] {
+        &self.variants
+    }
+}
+
+impl<P> Default for Schema<P> {
+    fn default() -> Self {
+        Schema::new()
+    }
+}
+
+impl<P> Serialize for Schema<P>
+where
+    P: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let variants: Vec<_> = self.variants.iter().map(TaggedSchema).collect();
+
+        let mut state = serializer.serialize_map(Some(1))?;
+        state.serialize_entry("oneOf", &variants)?;
+        state.end()
+    }
+}
+
+
+/// A variant of a [`Schema`], consisting of a tag, its tagging format, and
+/// the schema of its payload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant<P> {
+    tag:     String,
+    format:  TagFormat,
+    payload: P,
+}
+
+impl<P> Variant<P> {
+    /// Creates a new variant with the given tag, tagging format, and payload
+    /// schema.
+    pub fn new<T>(tag: T, format: TagFormat, payload: P) -> Self
+    where
+        T: Into<String>,
+    {
+        Variant {
+            tag: tag.into(),
+            format,
+            payload,
+        }
+    }
+
+    /// Returns the tag of this variant.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the tagging format of this variant.
+    pub fn format(&self) -> &TagFormat {
+        &self.format
+    }
+
+    /// Returns the payload schema of this variant.
+    pub fn payload(&self) -> &P {
+        &self.payload
+    }
+}
+
+
+/// The schema of a single variant, embedded according to its tagging format.
+struct TaggedSchema<'a, P>(&'a Variant<P>);
+
+impl<'a, P> Serialize for TaggedSchema<'a, P>
+where
+    P: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let variant = self.0;
+        let tag = Const(&variant.tag);
+        let payload = &variant.payload;
+
+        match &variant.format {
+            TagFormat::External => {
+                let properties = Property(&variant.tag, payload);
+                object(serializer, &properties, &[&variant.tag])
+            },
+            TagFormat::Internal { tag_key } => {
+                let properties = Property(tag_key, &tag);
+                let embedded = Object {
+                    properties: &properties,
+                    required:   &[&**tag_key],
+                    closed:     false,
+                };
+
+                let mut state = serializer.serialize_map(Some(1))?;
+                state.serialize_entry("allOf", &Pair(payload, &embedded))?;
+                state.end()
+            },
+            TagFormat::AdjTuple => {
+                let mut state = serializer.serialize_map(Some(5))?;
+                state.serialize_entry("type", "array")?;
+                state.serialize_entry("prefixItems", &Pair(&tag, payload))?;
+                state.serialize_entry("items", &false)?;
+                state.serialize_entry("minItems", &2)?;
+                state.serialize_entry("maxItems", &2)?;
+                state.end()
+            },
+            TagFormat::AdjMap { tag_key, value_key }
+            | TagFormat::AdjStruct {
+                tag_key, value_key, ..
+            } => {
+                let properties = Properties((tag_key, &tag), (value_key, payload));
+                object(serializer, &properties, &[&**tag_key, &**value_key])
+            },
+        }
+    }
+}
+
+fn object<S, P>(serializer: S, properties: &P, required: &[&str]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    P: Serialize,
+{
+    Object {
+        properties,
+        required,
+        closed: true,
+    }
+    .serialize(serializer)
+}
+
+
+/// A JSON Schema object with the given properties.
+struct Object<'a, P> {
+    properties: &'a P,
+    required:   &'a [&'a str],
+    closed:     bool,
+}
+
+impl<'a, P> Serialize for Object<'a, P>
+where
+    P: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(None)?;
+        state.serialize_entry("type", "object")?;
+        state.serialize_entry("properties", self.properties)?;
+        state.serialize_entry("required", self.required)?;
+        if self.closed {
+            state.serialize_entry("additionalProperties", &false)?;
+        }
+        state.end()
+    }
+}
+
+
+/// A JSON Schema matching only the given string.
+struct Const<'a>(&'a str);
+
+impl<'a> Serialize for Const<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(1))?;
+        state.serialize_entry("const", self.0)?;
+        state.end()
+    }
+}
+
+
+/// A map containing a single property name and its schema.
+struct Property<'a, V: 'a>(&'a str, &'a V);
+
+impl<'a, V> Serialize for Property<'a, V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(1))?;
+        state.serialize_entry(self.0, self.1)?;
+        state.end()
+    }
+}
+
+
+/// A map containing two property names and their schemas.
+struct Properties<'a, A: 'a, B: 'a>((&'a str, &'a A), (&'a str, &'a B));
+
+impl<'a, A, B> Serialize for Properties<'a, A, B>
+where
+    A: Serialize,
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry((self.0).0, (self.0).1)?;
+        state.serialize_entry((self.1).0, (self.1).1)?;
+        state.end()
+    }
+}
+
+
+/// A sequence of two schemas.
+struct Pair<'a, A: 'a, B: 'a>(&'a A, &'a B);
+
+impl<'a, A, B> Serialize for Pair<'a, A, B>
+where
+    A: Serialize,
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeTuple;
+
+        let mut state = serializer.serialize_tuple(2)?;
+        state.serialize_element(self.0)?;
+        state.serialize_element(self.1)?;
+        state.end()
+    }
+}
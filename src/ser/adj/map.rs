@@ -70,7 +70,9 @@
 //! }
 //! ```
 
-use std::fmt::Display;
+use core::fmt::Display;
+
+use alloc::vec::Vec;
 
 use serde;
 
@@ -86,7 +86,9 @@
 //! }
 //! ```
 
-use std::fmt::Display;
+use core::fmt::Display;
+
+use alloc::vec::Vec;
 
 use serde;
 
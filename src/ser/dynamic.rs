@@ -0,0 +1,313 @@
+//! Serialization of tagged values in a tagging format selected at runtime.
+//!
+//! The formats provided in [`ser`](crate::ser) differ in their parameters,
+//! e.g. [`internal`](crate::ser::internal) requires a tag key while
+//! [`adj::struc`](crate::ser::adj::struc) requires a struct name and two keys.
+//! This module dispatches to the respective format based on a [`TagFormat`],
+//! which carries these parameters, thus allowing the format to be chosen via
+//! configuration. The deserialization counterpart is provided in
+//! [`de::dynamic`](crate::de::dynamic).
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::format::TagFormat;
+//!
+//! # fn main() {
+//! let format = TagFormat::adj_map("type", "data");
+//!
+//! let mut out = Vec::new();
+//! let mut serializer = serde_json::Serializer::new(&mut out);
+//! serde_tagged::ser::dynamic::serialize(&mut serializer, &format, "foo", &42).unwrap();
+//!
+//! assert_eq!(out, br#"{"type":"foo","data":42}"#);
+//! # }
+//! ```
+
+use crate::format::{TagFormat, OWNED_ADJ_STRUCT};
+
+use serde;
+
+
+/// Serializes the specified tag and value in the given tagging format.
+///
+/// See the respective format module in [`ser`](crate::ser) for details on
+/// each format. Internally tagged structs are always serialized as maps, and
+/// the name and keys of an [`AdjStruct`](TagFormat::AdjStruct) format must
+/// be borrowed, see [`TagFormat`] for details.
+pub fn serialize<S, T, V>(
+    serializer: S,
+    format: &TagFormat,
+    tag: &T,
+    value: &V,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: serde::Serialize + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    use alloc::borrow::Cow;
+
+    match *format {
+        TagFormat::External => crate::ser::external::serialize(serializer, tag, value),
+        TagFormat::Internal { ref tag_key } => {
+            value.serialize(InternalSerializer::new(serializer, tag_key, tag))
+        },
+        TagFormat::AdjTuple => crate::ser::adj::tuple::serialize(serializer, tag, value),
+        TagFormat::AdjMap {
+            ref tag_key,
+            ref value_key,
+        } => crate::ser::adj::map::serialize(serializer, &**tag_key, tag, &**value_key, value),
+        TagFormat::AdjStruct {
+            name: Cow::Borrowed(name),
+            tag_key: Cow::Borrowed(tag_key),
+            value_key: Cow::Borrowed(value_key),
+        } => crate::ser::adj::struc::serialize(serializer, name, tag_key, tag, value_key, value),
+        TagFormat::AdjStruct { .. } => Err(serde::ser::Error::custom(OWNED_ADJ_STRUCT)),
+    }
+}
+
+
+/// A serializer embedding a tag under a key that may not be `'static` into
+/// the provided value, see [`ser::internal`](crate::ser::internal).
+///
+/// Serde requires field names to be `'static`, thus structs are serialized as
+/// maps by this serializer. Values not involving the tag key are serialized
+/// via [`ser::internal::Serializer`](crate::ser::internal::Serializer).
+struct InternalSerializer<'a, S, T: ?Sized + 'a> {
+    delegate: S,
+    tag_key:  &'a str,
+    tag:      &'a T,
+}
+
+impl<'a, S, T> InternalSerializer<'a, S, T>
+where
+    S: serde::Serializer,
+    T: serde::Serialize + ?Sized + 'a,
+{
+    fn new(delegate: S, tag_key: &'a str, tag: &'a T) -> Self {
+        InternalSerializer {
+            delegate,
+            tag_key,
+            tag,
+        }
+    }
+
+    /// Returns the serializer for values not involving the tag key.
+    fn keyless(self) -> crate::ser::internal::Serializer<'a, S, T> {
+        crate::ser::internal::Serializer::new(self.delegate, "", self.tag)
+    }
+}
+
+impl<'a, S, T> serde::Serializer for InternalSerializer<'a, S, T>
+where
+    S: serde::Serializer,
+    T: serde::Serialize + ?Sized + 'a,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = StructAsMap<S::SerializeMap>;
+    type SerializeTupleVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_bool(value)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_i8(value)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_i16(value)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_i32(value)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_i64(value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_u8(value)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_u16(value)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_u32(value)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_u64(value)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_f32(value)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_f64(value)
+    }
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_char(value)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_str(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_bytes(value)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_none()
+    }
+
+    fn serialize_some<V>(self, value: &V) -> Result<Self::Ok, Self::Error>
+    where
+        V: serde::Serialize + ?Sized,
+    {
+        self.keyless().serialize_some(value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.keyless().serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.keyless()
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_variant<V>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: serde::Serialize + ?Sized,
+    {
+        self.keyless()
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.keyless()
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.keyless()
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.keyless().serialize_tuple(len)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.keyless().serialize_seq(len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut state = self.delegate.serialize_map(len.map(|len| len + 1))?;
+        state.serialize_entry(self.tag_key, self.tag)?;
+        Ok(state)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        use serde::ser::SerializeMap;
+
+        self.serialize_map(Some(0))?.end()
+    }
+
+    fn serialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: serde::Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.keyless().serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len)).map(StructAsMap)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+
+/// Serializes the fields of a struct as entries of a map.
+struct StructAsMap<M>(M);
+
+impl<M> serde::ser::SerializeStruct for StructAsMap<M>
+where
+    M: serde::ser::SerializeMap,
+{
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_field<V>(&mut self, key: &'static str, value: &V) -> Result<(), Self::Error>
+    where
+        V: serde::Serialize + ?Sized,
+    {
+        self.0.serialize_entry(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.0.end()
+    }
+}
@@ -60,7 +60,9 @@
 //! { "my-tag": { "bar": "baz" } }
 //! ```
 
-use std::fmt::Display;
+use core::fmt::Display;
+
+use alloc::vec::Vec;
 
 use serde;
 
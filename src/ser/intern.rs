@@ -0,0 +1,135 @@
+//! Serialization of interned tags.
+//!
+//! Documents containing many tagged values usually repeat the same tags over
+//! and over again. Interning replaces all but the first occurrence of each
+//! tag in a stream by a numeric id: The first occurrence of a tag is
+//! serialized as string and implicitly assigns the next free id (starting at
+//! zero) to the tag, all later occurrences are serialized as this id. The
+//! deserialization counterpart is provided in
+//! [`de::intern`](crate::de::intern).
+//!
+//! As ids are assigned in the order in which tags are serialized, the tags
+//! have to be deserialized in the same order. Thus interning can only be used
+//! with formats storing the tag before the value, i.e.
+//! [`ser::external`](crate::ser::external) and
+//! [`ser::adj::tuple`](crate::ser::adj::tuple), and all tagged values sharing
+//! a [`Table`] have to be part of the same stream.
+//!
+//! Data formats like JSON represent map keys as strings, thus ids cannot be
+//! distinguished from tags consisting only of ASCII digits. Serializing such
+//! a tag results in an error.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::ser::intern::Table;
+//! use serde_tagged::tagged::External;
+//!
+//! # fn main() {
+//! let table = Table::new();
+//!
+//! let values = vec![
+//!     External::new(table.tag("circle"), 1),
+//!     External::new(table.tag("rect"), 2),
+//!     External::new(table.tag("circle"), 3),
+//! ];
+//!
+//! let json = serde_json::to_string(&values).unwrap();
+//! assert_eq!(json, r#"[{"circle":1},{"rect":2},{"0":3}]"#);
+//! # }
+//! ```
+
+use core::cell::RefCell;
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+
+use serde;
+
+
+/// A table assigning ids to tags in the order of their first serialization.
+///
+/// See the [module documentation](crate::ser::intern) for details.
+#[derive(Debug, Default)]
+pub struct Table {
+    ids: RefCell<BTreeMap<String, u64>>,
+}
+
+impl Table {
+    /// Creates a new, empty table.
+    pub fn new() -> Self {
+        Table {
+            ids: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns a serializable representation of the given tag, interned via
+    /// this table.
+    pub fn tag<'a>(&'a self, tag: &'a str) -> Tag<'a> {
+        Tag { table: self, tag }
+    }
+
+    /// Returns the id assigned to the given tag, or `None` if the tag has not
+    /// been serialized yet.
+    pub fn id(&self, tag: &str) -> Option<u64> {
+        self.ids.borrow().get(tag).copied()
+    }
+
+    /// Returns the number of tags that have been assigned an id.
+    pub fn len(&self) -> usize {
+        self.ids.borrow().len()
+    }
+
+    /// Returns `true` if no tag has been assigned an id yet.
+    pub fn is_empty(&self) -> bool {
+        self.ids.borrow().is_empty()
+    }
+}
+
+
+/// A tag interned via a [`Table`].
+///
+/// Serialized as string on its first serialization via the table, and as
+/// numeric id afterwards.
+#[derive(Clone, Copy, Debug)]
+pub struct Tag<'a> {
+    table: &'a Table,
+    tag:   &'a str,
+}
+
+impl<'a> serde::Serialize for Tag<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        if let Some(id) = self.table.id(self.tag) {
+            return serializer.serialize_u64(id);
+        }
+
+        if is_id(self.tag) {
+            return Err(S::Error::custom(format_args!(
+                "cannot intern tag `{}` consisting only of digits",
+                self.tag
+            )));
+        }
+
+        {
+            let mut ids = self.table.ids.borrow_mut();
+            let id = ids.len() as u64;
+            ids.insert(self.tag.to_string(), id);
+        }
+
+        serializer.serialize_str(self.tag)
+    }
+}
+
+
+/// Returns `true` if the given string could be mistaken for an id.
+pub(crate) fn is_id(tag: &str) -> bool {
+    !tag.is_empty() && tag.bytes().all(|b| b.is_ascii_digit())
+}
@@ -1,8 +1,19 @@
 //! Serialization of tagged values.
 
 pub mod adj;
+pub mod dynamic;
 pub mod external;
+pub mod intern;
 pub mod internal;
+pub mod native;
+pub mod nominal;
+pub mod readable;
+pub mod variant;
+
+#[cfg(feature = "std")]
+mod scope;
+#[cfg(feature = "std")]
+pub use self::scope::*;
 
 
 use serde;
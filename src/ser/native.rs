@@ -0,0 +1,127 @@
+//! Serialization of tagged values using the native tag syntax of the data
+//! format.
+//!
+//! Some data formats support tags natively, e.g. YAML (`!tag value`), CBOR
+//! (semantic tags), or MessagePack (extension types). As serde does not
+//! provide a way to pass tags to a data format, this module uses a magic
+//! newtype struct instead: The tagged value is serialized as newtype struct
+//! with a given name, wrapping a tuple with two elements, the first of which
+//! is the tag and the second the value.
+//!
+//! Data formats supporting this protocol recognize the struct name in
+//! `Serializer::serialize_newtype_struct` and emit the tuple elements as tag
+//! and value using their native syntax. Likewise, when requested to
+//! deserialize a newtype struct with this name via
+//! `Deserializer::deserialize_newtype_struct`, they pass a deserializer
+//! providing a tuple of tag and value to `Visitor::visit_newtype_struct`.
+//!
+//! Data formats that do not support this protocol usually serialize newtype
+//! structs transparently, in which case the tagged value is represented
+//! exactly like with [adjacent tagging using tuples](crate::ser::adj::tuple).
+//! The deserialization functions in [`de::native`](crate::de::native) accept
+//! both representations.
+//!
+//! The name of the newtype struct is specified on each call, as data formats
+//! recognize different names. [`NAME`] is intended for data formats
+//! implementing this protocol specifically for tagged values. Some data
+//! formats recognize other names for the same protocol, e.g. `rmp-serde`
+//! recognizes [`MSGPACK_EXT`], for which it expects an `i8` tag and a byte
+//! value, and emits them as MessagePack extension type.
+//!
+//! Note that data formats may restrict the types of tags they support
+//! natively, e.g. to strings or integers.
+//!
+//! # Examples serializing to JSON
+//!
+//! Serializing a value
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::ser::native::NAME;
+//!
+//! # fn main() {
+//! let foo: i32 = 42;
+//!
+//! let mut serializer = serde_json::Serializer::new(std::io::stdout());
+//! serde_tagged::ser::native::serialize(&mut serializer, NAME, "bar", &foo).unwrap();
+//! # }
+//! ```
+//!
+//! with a tag value of `"bar"` will produce
+//!
+//! ```json
+//! [ "bar", 42 ]
+//! ```
+//!
+//! as JSON does not support tags and serializes newtype structs
+//! transparently.
+
+use serde;
+
+
+/// The name of the newtype struct used to pass tagged values to data formats
+/// implementing the protocol for this crate.
+///
+/// See the [module documentation](crate::ser::native) for a description of
+/// the protocol.
+pub const NAME: &str = "$serde_tagged::native";
+
+/// The name of the newtype struct used to pass MessagePack extension types to
+/// `rmp-serde`.
+///
+/// `rmp-serde` requires the tag to be an `i8` and the value to be serialized
+/// as bytes (e.g. via `serde_bytes`).
+pub const MSGPACK_EXT: &str = "_ExtStruct";
+
+
+/// Serializes the specified tag and value using the native tag syntax of the
+/// data format.
+///
+/// The tag-value pair will be passed to the specified serializer as newtype
+/// struct with the given name, wrapping a tuple of tag and value. For more
+/// information on this tag-format, see the [module
+/// documentation](crate::ser::native).
+///
+/// # Note
+///
+/// Unlike most other formats, this format does not provide a `Serializer`
+/// implementation, as the value has to be passed to the underlying serializer
+/// as a whole.
+pub fn serialize<S, T, V>(
+    serializer: S,
+    name: &'static str,
+    tag: &T,
+    value: &V,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: serde::Serialize + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    serializer.serialize_newtype_struct(name, &Tagged { tag, value })
+}
+
+
+struct Tagged<'a, T, V>
+where
+    T: ?Sized + 'a,
+    V: ?Sized + 'a,
+{
+    tag:   &'a T,
+    value: &'a V,
+}
+
+impl<'a, T, V> serde::Serialize for Tagged<'a, T, V>
+where
+    T: serde::Serialize + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::adj::tuple::serialize(serializer, self.tag, self.value)
+    }
+}
@@ -0,0 +1,633 @@
+//! Serialization of values tagged with their type name.
+//!
+//! Serde passes the name of a struct (or enum) to the serializer, e.g. via
+//! `serialize_struct`. This module uses that name as tag, thus the name
+//! given in the Rust code or via `#[serde(rename = "...")]` is the single
+//! source of truth for tags. The deserialization counterpart is provided in
+//! [`de::nominal`](crate::de::nominal).
+//!
+//! For enums, the name of the enum (not that of the variant) is used.
+//!
+//! # Unsupported types
+//!
+//! Values that are not passed to the serializer with a name cannot be tagged
+//! this way and result in an error. Besides primitives, sequences, and maps,
+//! this includes structs that serde serializes as map, notably structs
+//! containing a `#[serde(flatten)]` field.
+//!
+//! # Example
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::format::TagFormat;
+//!
+//! #[derive(Serialize)]
+//! #[serde(rename = "point")]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! # fn main() {
+//! let point = Point { x: 1, y: 2 };
+//! let format = TagFormat::internal("type");
+//!
+//! let mut out = Vec::new();
+//! let mut serializer = serde_json::Serializer::new(&mut out);
+//! serde_tagged::ser::nominal::serialize(&mut serializer, &format, &point).unwrap();
+//!
+//! assert_eq!(out, br#"{"type":"point","x":1,"y":2}"#);
+//! # }
+//! ```
+
+use crate::format::TagFormat;
+use crate::util::ser::content::{self, Content, ContentSerializer};
+use crate::util::ser::forward;
+
+use core::fmt;
+
+use serde;
+
+
+/// Serializes the given value, tagged with its type name, in the given
+/// format.
+///
+/// Returns an error if the value does not have a name. For more information
+/// on this tagging method, see the [module documentation](crate::ser::nominal).
+pub fn serialize<S, V>(serializer: S, format: &TagFormat, value: &V) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: serde::Serialize + ?Sized,
+{
+    value.serialize(Serializer::new(serializer, format))
+}
+
+
+/// A serializer that tags values with their type name.
+///
+/// The type name is taken from the first call the value makes to this
+/// serializer, e.g. `serialize_struct`. The value is then serialized with
+/// the name as tag in the given format, as done by
+/// [`ser::dynamic`](crate::ser::dynamic). For more information on this
+/// tagging method, see the [module documentation](crate::ser::nominal).
+///
+/// # Warning
+///
+/// The fields of structs, tuple structs, and enum variants with fields are
+/// passed to the serializer one after another, whereas the tagging formats
+/// require the value as a whole. To serialize such values, this serializer
+/// buffers their fields on the heap.
+pub struct Serializer<'a, S> {
+    delegate: S,
+    format:   &'a TagFormat,
+}
+
+impl<'a, S> Serializer<'a, S>
+where
+    S: serde::Serializer,
+{
+    /// Creates a new Serializer with the specified tagging format and
+    /// underlying serializer.
+    pub fn new(delegate: S, format: &'a TagFormat) -> Self {
+        Serializer { delegate, format }
+    }
+
+    fn serialize_named<V>(self, name: &'static str, value: &V) -> Result<S::Ok, S::Error>
+    where
+        V: serde::Serialize + ?Sized,
+    {
+        crate::ser::dynamic::serialize(self.delegate, self.format, name, value)
+    }
+
+    fn buffer_named<C>(self, name: &'static str, state: C) -> SerializeNamed<'a, S, C> {
+        SerializeNamed {
+            delegate: self.delegate,
+            format: self.format,
+            name,
+            state,
+        }
+    }
+
+    fn unnamed<T>(self) -> Result<T, S::Error> {
+        use serde::ser::Error;
+
+        Err(S::Error::custom("cannot determine type name of value"))
+    }
+}
+
+impl<'a, S> serde::Serializer for Serializer<'a, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    type SerializeSeq = serde::ser::Impossible<S::Ok, S::Error>;
+    type SerializeTuple = serde::ser::Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = SerializeNamed<'a, S, content::SerializeTupleStruct<S::Error>>;
+    type SerializeTupleVariant = SerializeNamed<'a, S, content::SerializeTupleVariant<S::Error>>;
+    type SerializeMap = serde::ser::Impossible<S::Ok, S::Error>;
+    type SerializeStruct = SerializeNamed<'a, S, content::SerializeStruct<S::Error>>;
+    type SerializeStructVariant = SerializeNamed<'a, S, content::SerializeStructVariant<S::Error>>;
+
+    fn serialize_bool(self, _v: bool) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_char(self, _v: char) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.unnamed()
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.serialize_named(name, &forward::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.serialize_named(name, &forward::UnitVariant(name, variant_index, variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.serialize_named(name, &forward::NewtypeStruct(name, value))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let value = forward::NewtypeVariant(name, variant_index, variant, value);
+        self.serialize_named(name, &value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let state = ContentSerializer::new().serialize_tuple_struct(name, len)?;
+        Ok(self.buffer_named(name, state))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let state =
+            ContentSerializer::new().serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(self.buffer_named(name, state))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.unnamed()
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let state = ContentSerializer::new().serialize_struct(name, len)?;
+        Ok(self.buffer_named(name, state))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let state =
+            ContentSerializer::new().serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(self.buffer_named(name, state))
+    }
+
+    fn collect_str<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: fmt::Display + ?Sized,
+    {
+        self.unnamed()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+
+/// Buffers the fields of a named compound value and serializes the value
+/// tagged with its name once complete.
+#[doc(hidden)]
+pub struct SerializeNamed<'a, S, C> {
+    delegate: S,
+    format:   &'a TagFormat,
+    name:     &'static str,
+    state:    C,
+}
+
+impl<'a, S, C> serde::ser::SerializeTupleStruct for SerializeNamed<'a, S, C>
+where
+    S: serde::Serializer,
+    C: serde::ser::SerializeTupleStruct<Ok = Content, Error = S::Error>,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.state.serialize_field(value)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let value = self.state.end()?;
+        crate::ser::dynamic::serialize(self.delegate, self.format, self.name, &value)
+    }
+}
+
+impl<'a, S, C> serde::ser::SerializeTupleVariant for SerializeNamed<'a, S, C>
+where
+    S: serde::Serializer,
+    C: serde::ser::SerializeTupleVariant<Ok = Content, Error = S::Error>,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.state.serialize_field(value)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let value = self.state.end()?;
+        crate::ser::dynamic::serialize(self.delegate, self.format, self.name, &value)
+    }
+}
+
+impl<'a, S, C> serde::ser::SerializeStruct for SerializeNamed<'a, S, C>
+where
+    S: serde::Serializer,
+    C: serde::ser::SerializeStruct<Ok = Content, Error = S::Error>,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.state.serialize_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.state.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let value = self.state.end()?;
+        crate::ser::dynamic::serialize(self.delegate, self.format, self.name, &value)
+    }
+}
+
+impl<'a, S, C> serde::ser::SerializeStructVariant for SerializeNamed<'a, S, C>
+where
+    S: serde::Serializer,
+    C: serde::ser::SerializeStructVariant<Ok = Content, Error = S::Error>,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.state.serialize_field(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.state.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let value = self.state.end()?;
+        crate::ser::dynamic::serialize(self.delegate, self.format, self.name, &value)
+    }
+}
+
+
+/// Returns the type name that the given value passes to the serializer, or
+/// `None` if it does not pass any.
+///
+/// This aborts the serialization of the value as soon as the name is known,
+/// i.e. usually on the first call to the serializer.
+pub fn name<V>(value: &V) -> Option<&'static str>
+where
+    V: serde::Serialize + ?Sized,
+{
+    match value.serialize(NameSerializer) {
+        Err(NameError::Name(name)) => Some(name),
+        _ => None,
+    }
+}
+
+
+/// The result of a [`NameSerializer`], returned as error to abort
+/// serialization early.
+#[derive(Debug)]
+enum NameError {
+    Name(&'static str),
+    Unnamed,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NameError::Name(name) => write!(f, "type name `{}`", name),
+            NameError::Unnamed => write!(f, "value without type name"),
+        }
+    }
+}
+
+impl serde::ser::StdError for NameError {}
+
+impl serde::ser::Error for NameError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        NameError::Unnamed
+    }
+}
+
+
+/// A serializer capturing the type name of a value.
+struct NameSerializer;
+
+impl NameSerializer {
+    fn unnamed<T>(self) -> Result<T, NameError> {
+        Err(NameError::Unnamed)
+    }
+}
+
+impl serde::Serializer for NameSerializer {
+    type Ok = ();
+    type Error = NameError;
+
+    type SerializeSeq = serde::ser::Impossible<(), NameError>;
+    type SerializeTuple = serde::ser::Impossible<(), NameError>;
+    type SerializeTupleStruct = serde::ser::Impossible<(), NameError>;
+    type SerializeTupleVariant = serde::ser::Impossible<(), NameError>;
+    type SerializeMap = serde::ser::Impossible<(), NameError>;
+    type SerializeStruct = serde::ser::Impossible<(), NameError>;
+    type SerializeStructVariant = serde::ser::Impossible<(), NameError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_none(self) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<(), NameError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.unnamed()
+    }
+
+    fn serialize_unit(self) -> Result<(), NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, _value: &T) -> Result<(), NameError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), NameError>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NameError> {
+        self.unnamed()
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NameError> {
+        Err(NameError::Name(name))
+    }
+
+    fn collect_str<T>(self, _value: &T) -> Result<(), NameError>
+    where
+        T: fmt::Display + ?Sized,
+    {
+        self.unnamed()
+    }
+}
@@ -0,0 +1,82 @@
+//! Serialization of tags depending on whether the data format is
+//! human-readable.
+//!
+//! This module provides the tag representation [`Tag`], which is serialized
+//! as string if the data format is human-readable (as reported by
+//! `Serializer::is_human_readable`) and as numeric id (as assigned via
+//! [`TagIds`]) otherwise. Thus the same types yield readable tags in e.g.
+//! JSON logs and compact tags in binary formats.
+//!
+//! As this only affects how the tag itself is represented, [`Tag`] can be
+//! used with every format in [`ser`](crate::ser). The deserialization
+//! counterpart is provided in [`de::readable`](crate::de::readable).
+//!
+//! Note that values buffered by the `Serializer` implementations of the
+//! formats are always treated as human-readable. Tags nested in such values
+//! should thus only be serialized via the `serialize` functions.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::format::TagIds;
+//! use serde_tagged::ser::readable::Tag;
+//!
+//! const IDS: TagIds = TagIds::new(&[("circle", 1), ("rect", 2)]);
+//!
+//! # fn main() {
+//! let mut out = Vec::new();
+//! let mut serializer = serde_json::Serializer::new(&mut out);
+//!
+//! let tag = Tag::new(&IDS, "rect");
+//! serde_tagged::ser::external::serialize(&mut serializer, &tag, &42).unwrap();
+//!
+//! assert_eq!(out, br#"{"rect":42}"#);
+//! # }
+//! ```
+
+use crate::format::TagIds;
+
+use serde;
+
+
+/// A tag serialized either as string or as numeric id, depending on whether
+/// the data format is human-readable.
+///
+/// Serialization fails if the tag has no id assigned in the given
+/// [`TagIds`], regardless of the data format.
+#[derive(Clone, Copy, Debug)]
+pub struct Tag<'a> {
+    ids: &'a TagIds,
+    tag: &'a str,
+}
+
+impl<'a> Tag<'a> {
+    /// Creates a new tag representation for the given tag, using the given
+    /// id assignment.
+    pub fn new(ids: &'a TagIds, tag: &'a str) -> Self {
+        Tag { ids, tag }
+    }
+}
+
+impl<'a> serde::Serialize for Tag<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let (id, tag) = self
+            .ids
+            .id(self.tag)
+            .ok_or_else(|| S::Error::custom(format_args!("unknown tag `{}`", self.tag)))?;
+
+        if serializer.is_human_readable() {
+            serializer.serialize_str(tag)
+        } else {
+            serializer.serialize_u64(id)
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Tag resolvers scoped to the current thread.
+//!
+//! This is the serialization counterpart to
+//! [`de::with_registry`](crate::de::with_registry). Using
+//! [`with_tag_resolver`], a tag resolver (i.e. any value that can be used to
+//! determine the tag of a value, e.g. a map from `TypeId` to tag) can be made
+//! available to `Serialize` implementations for the duration of a closure on
+//! the current thread, which can access it via [`with_current_tag_resolver`].
+
+use crate::util::scope::{self, Stack};
+
+use core::cell::RefCell;
+
+use alloc::vec::Vec;
+
+
+std::thread_local! {
+    static RESOLVERS: Stack = const { RefCell::new(Vec::new()) };
+}
+
+
+/// Executes the given closure with the given tag resolver in scope.
+///
+/// During execution of the closure, the resolver can be accessed on the
+/// current thread via [`with_current_tag_resolver`]. Scopes can be nested, in
+/// which case the inner-most resolver of the requested type is used.
+pub fn with_tag_resolver<R, F, T>(resolver: &R, func: F) -> T
+where
+    R: 'static,
+    F: FnOnce() -> T,
+{
+    scope::enter(&RESOLVERS, resolver, func)
+}
+
+/// Executes the given closure with the inner-most tag resolver of type `R`
+/// that is in scope on the current thread.
+///
+/// Returns `None` (without executing the closure) if no resolver of this type
+/// is in scope.
+pub fn with_current_tag_resolver<R, F, T>(func: F) -> Option<T>
+where
+    R: 'static,
+    F: FnOnce(&R) -> T,
+{
+    scope::current(&RESOLVERS, func)
+}
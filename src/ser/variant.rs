@@ -0,0 +1,78 @@
+//! Serialization of tagged values as enum variants.
+//!
+//! Tagging a value using this strategy serializes it as newtype variant of an
+//! enum, where the tag is the variant name and the variant index is taken
+//! from a list of all known tags (see [`Variants`]). Thus, data formats
+//! serialize the value using their native enum representation, e.g. an index
+//! followed by the value for bincode or postcard, `<tag>(<value>)` for RON,
+//! and `!<tag> <value>` for YAML.
+//!
+//! Unlike the other formats, this format can be deserialized by data formats
+//! that do not support `deserialize_any`, as long as they support enums.
+//!
+//! # Examples serializing to JSON
+//!
+//! Serializing a value
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::format::Variants;
+//!
+//! const VARIANTS: Variants = Variants::new("Shape", &["circle", "rect"]);
+//!
+//! # fn main() {
+//! let foo: i32 = 42;
+//!
+//! let mut serializer = serde_json::Serializer::new(std::io::stdout());
+//! serde_tagged::ser::variant::serialize(&mut serializer, &VARIANTS, "rect", &foo).unwrap();
+//! # }
+//! ```
+//!
+//! with a tag value of `"rect"` will produce
+//!
+//! ```json
+//! { "rect": 42 }
+//! ```
+//!
+//! as JSON represents newtype variants as map with a single entry.
+
+use crate::format::Variants;
+
+use serde;
+
+
+/// Serializes the specified tag and value as newtype variant.
+///
+/// The variant index of the tag is looked up in the given variants. Returns
+/// an error if the tag is not contained in them. For more information on this
+/// tag-format, see the [module documentation](crate::ser::variant).
+///
+/// # Note
+///
+/// Unlike the other formats, this format does not provide a `Serializer`
+/// implementation, as the value has to be passed to the underlying serializer
+/// as a whole.
+pub fn serialize<S, V>(
+    serializer: S,
+    variants: &Variants,
+    tag: &str,
+    value: &V,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: serde::Serialize + ?Sized,
+{
+    use serde::ser::Error;
+
+    match variants.index(tag) {
+        Some((index, tag)) => {
+            serializer.serialize_newtype_variant(variants.name(), index, tag, value)
+        },
+        None => Err(S::Error::custom(format_args!(
+            "unknown variant tag `{}`",
+            tag
+        ))),
+    }
+}
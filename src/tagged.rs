@@ -0,0 +1,493 @@
+//! Generic wrapper types for tagged values.
+//!
+//! Each type of this module represents a tag and a value of known type,
+//! which are serialized using one of the tagging formats and can be
+//! deserialized using the corresponding deserialization function with
+//! [`WithTag`]. Unlike the functions in [`ser`](crate::ser) and
+//! [`de`](crate::de), these types can be used directly inside derived
+//! structs or containers, e.g. as `Vec<External<String, Value>>`.
+//!
+//! The keys used by the [`Internal`], [`AdjMap`] and [`AdjStruct`] formats are
+//! specified by a marker type implementing [`TagKeys`], defaulting to
+//! [`DefaultKeys`]. Code generic over the tagging format can use [`Tagged`]
+//! instead, which takes the format as type parameter.
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::tagged::{AdjMap, External};
+//! use serde_tagged::format::TagKeys;
+//!
+//! struct Keys;
+//!
+//! impl TagKeys for Keys {
+//!     const TAG_KEY: &'static str = "kind";
+//!     const VALUE_KEY: &'static str = "data";
+//! }
+//!
+//! # fn main() {
+//! let values = vec![
+//!     External::new("a".to_owned(), 1),
+//!     External::new("b".to_owned(), 2),
+//! ];
+//!
+//! let json = serde_json::to_string(&values).unwrap();
+//! assert_eq!(json, r#"[{"a":1},{"b":2}]"#);
+//!
+//! let de: Vec<External<String, i32>> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(de, values);
+//!
+//! let value: AdjMap<String, i32, Keys> = AdjMap::new("a".to_owned(), 1);
+//!
+//! let json = serde_json::to_string(&value).unwrap();
+//! assert_eq!(json, r#"{"kind":"a","data":1}"#);
+//! # }
+//! ```
+
+use crate::de::WithTag;
+use crate::format::{DefaultKeys, Format, TagKeys};
+
+use core::marker::PhantomData;
+
+use alloc::string::String;
+
+use serde;
+
+
+/// An externally tagged value.
+///
+/// See [`ser::external`](crate::ser::external) for a description of this
+/// tagging format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct External<T, V> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+}
+
+impl<T, V> External<T, V> {
+    /// Creates a new externally tagged value.
+    pub fn new(tag: T, value: V) -> Self {
+        External { tag, value }
+    }
+
+    /// Returns tag and value as tuple.
+    pub fn into_parts(self) -> (T, V) {
+        (self.tag, self.value)
+    }
+}
+
+impl<T, V> serde::Serialize for External<T, V>
+where
+    T: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::external::serialize(serializer, &self.tag, &self.value)
+    }
+}
+
+impl<'de, T, V> serde::Deserialize<'de> for External<T, V>
+where
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) = crate::de::external::deserialize(deserializer, WithTag::new())?;
+        Ok(External::new(tag, value))
+    }
+}
+
+
+/// An adjacently tagged value, using a tuple.
+///
+/// See [`ser::adj::tuple`](crate::ser::adj::tuple) for a description of this
+/// tagging format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AdjTuple<T, V> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+}
+
+impl<T, V> AdjTuple<T, V> {
+    /// Creates a new adjacently tagged value.
+    pub fn new(tag: T, value: V) -> Self {
+        AdjTuple { tag, value }
+    }
+
+    /// Returns tag and value as tuple.
+    pub fn into_parts(self) -> (T, V) {
+        (self.tag, self.value)
+    }
+}
+
+impl<T, V> serde::Serialize for AdjTuple<T, V>
+where
+    T: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::adj::tuple::serialize(serializer, &self.tag, &self.value)
+    }
+}
+
+impl<'de, T, V> serde::Deserialize<'de> for AdjTuple<T, V>
+where
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) = crate::de::adj::tuple::deserialize(deserializer, WithTag::new())?;
+        Ok(AdjTuple::new(tag, value))
+    }
+}
+
+
+/// Implements constructors and the standard traits for a wrapper type with
+/// keys or format, without requiring any bounds on the marker type.
+macro_rules! keyed {
+    ($name:ident < $($param:ident),* >, $marker:ident) => {
+        impl<$($param),*> $name<$($param),*> {
+            /// Creates a new tagged value.
+            pub fn new(tag: T, value: V) -> Self {
+                $name {
+                    tag,
+                    value,
+                    $marker: PhantomData,
+                }
+            }
+
+            /// Returns tag and value as tuple.
+            pub fn into_parts(self) -> (T, V) {
+                (self.tag, self.value)
+            }
+        }
+
+        impl<$($param),*> Clone for $name<$($param),*>
+        where
+            T: Clone,
+            V: Clone,
+        {
+            fn clone(&self) -> Self {
+                $name::new(self.tag.clone(), self.value.clone())
+            }
+        }
+
+        impl<$($param),*> Copy for $name<$($param),*>
+        where
+            T: Copy,
+            V: Copy,
+        {
+        }
+
+        impl<$($param),*> Default for $name<$($param),*>
+        where
+            T: Default,
+            V: Default,
+        {
+            fn default() -> Self {
+                $name::new(T::default(), V::default())
+            }
+        }
+
+        impl<$($param),*> core::fmt::Debug for $name<$($param),*>
+        where
+            T: core::fmt::Debug,
+            V: core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("tag", &self.tag)
+                    .field("value", &self.value)
+                    .finish()
+            }
+        }
+
+        impl<$($param),*> PartialEq for $name<$($param),*>
+        where
+            T: PartialEq,
+            V: PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.tag == other.tag && self.value == other.value
+            }
+        }
+
+        impl<$($param),*> Eq for $name<$($param),*>
+        where
+            T: Eq,
+            V: Eq,
+        {
+        }
+
+        impl<$($param),*> core::hash::Hash for $name<$($param),*>
+        where
+            T: core::hash::Hash,
+            V: core::hash::Hash,
+        {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.tag.hash(state);
+                self.value.hash(state);
+            }
+        }
+    };
+}
+
+
+/// An internally tagged value, using the tag key specified by `K`.
+///
+/// See [`ser::internal`](crate::ser::internal) for a description of this
+/// tagging format.
+pub struct Internal<T, V, K = DefaultKeys> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+
+    _keys: PhantomData<fn() -> K>,
+}
+
+keyed!(Internal<T, V, K>, _keys);
+
+impl<T, V, K> serde::Serialize for Internal<T, V, K>
+where
+    T: serde::Serialize,
+    V: serde::Serialize,
+    K: TagKeys,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::internal::serialize(serializer, K::TAG_KEY, &self.tag, &self.value)
+    }
+}
+
+impl<'de, T, V, K> serde::Deserialize<'de> for Internal<T, V, K>
+where
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    K: TagKeys,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) =
+            crate::de::internal::deserialize(deserializer, K::TAG_KEY, WithTag::new())?;
+
+        Ok(Internal::new(tag, value))
+    }
+}
+
+
+/// An adjacently tagged value, using a map with the keys specified by `K`.
+///
+/// See [`ser::adj::map`](crate::ser::adj::map) for a description of this
+/// tagging format.
+pub struct AdjMap<T, V, K = DefaultKeys> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+
+    _keys: PhantomData<fn() -> K>,
+}
+
+keyed!(AdjMap<T, V, K>, _keys);
+
+impl<T, V, K> serde::Serialize for AdjMap<T, V, K>
+where
+    T: serde::Serialize,
+    V: serde::Serialize,
+    K: TagKeys,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::adj::map::serialize(
+            serializer,
+            K::TAG_KEY,
+            &self.tag,
+            K::VALUE_KEY,
+            &self.value,
+        )
+    }
+}
+
+impl<'de, T, V, K> serde::Deserialize<'de> for AdjMap<T, V, K>
+where
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    K: TagKeys,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) = crate::de::adj::map::deserialize::<T, String, str, _, _>(
+            deserializer,
+            K::TAG_KEY,
+            K::VALUE_KEY,
+            WithTag::new(),
+        )?;
+
+        Ok(AdjMap::new(tag, value))
+    }
+}
+
+
+/// An adjacently tagged value, using a struct with the name and keys
+/// specified by `K`.
+///
+/// See [`ser::adj::struc`](crate::ser::adj::struc) for a description of this
+/// tagging format.
+pub struct AdjStruct<T, V, K = DefaultKeys> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+
+    _keys: PhantomData<fn() -> K>,
+}
+
+keyed!(AdjStruct<T, V, K>, _keys);
+
+impl<T, V, K> serde::Serialize for AdjStruct<T, V, K>
+where
+    T: serde::Serialize,
+    V: serde::Serialize,
+    K: TagKeys,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::ser::adj::struc::serialize(
+            serializer,
+            K::NAME,
+            K::TAG_KEY,
+            &self.tag,
+            K::VALUE_KEY,
+            &self.value,
+        )
+    }
+}
+
+impl<'de, T, V, K> serde::Deserialize<'de> for AdjStruct<T, V, K>
+where
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    K: TagKeys,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) = crate::de::adj::struc::deserialize(
+            deserializer,
+            K::NAME,
+            K::TAG_KEY,
+            K::VALUE_KEY,
+            WithTag::new(),
+        )?;
+
+        Ok(AdjStruct::new(tag, value))
+    }
+}
+
+
+/// A tagged value, using the tagging format specified by `F`.
+///
+/// This allows for types and functions generic over the tagging format, see
+/// [`Format`] for details. For example, `Tagged<format::Internal, T, V>`
+/// serializes exactly like `Internal<T, V>`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate serde_json;
+/// # extern crate serde_tagged;
+/// #
+/// use serde_tagged::format::{self, Format};
+/// use serde_tagged::tagged::Tagged;
+///
+/// fn roundtrip<F: Format>(value: &Tagged<F, String, i32>) -> Tagged<F, String, i32> {
+///     let json = serde_json::to_string(value).unwrap();
+///     serde_json::from_str(&json).unwrap()
+/// }
+///
+/// # fn main() {
+/// let value: Tagged<format::AdjTuple, _, _> = Tagged::new("foo".to_owned(), 42);
+/// assert_eq!(serde_json::to_string(&value).unwrap(), r#"["foo",42]"#);
+/// assert_eq!(roundtrip(&value), value);
+///
+/// let value: Tagged<format::External, _, _> = Tagged::new("foo".to_owned(), 42);
+/// assert_eq!(serde_json::to_string(&value).unwrap(), r#"{"foo":42}"#);
+/// assert_eq!(roundtrip(&value), value);
+/// # }
+/// ```
+pub struct Tagged<F, T, V> {
+    /// The tag.
+    pub tag: T,
+
+    /// The value.
+    pub value: V,
+
+    _format: PhantomData<fn() -> F>,
+}
+
+keyed!(Tagged<F, T, V>, _format);
+
+impl<F, T, V> serde::Serialize for Tagged<F, T, V>
+where
+    F: Format,
+    T: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        F::serialize(serializer, &self.tag, &self.value)
+    }
+}
+
+impl<'de, F, T, V> serde::Deserialize<'de> for Tagged<F, T, V>
+where
+    F: Format,
+    T: serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (tag, value) = F::deserialize(deserializer, WithTag::new())?;
+        Ok(Tagged::new(tag, value))
+    }
+}
@@ -65,11 +65,16 @@
 // - add special case for empty `Content::Seq` to
 //   `ContentDeserializer::deserialize_unit_struct`
 // - changed visibility of `ContentVisitor` and its `new` function to public
+// - derived `Clone` and `PartialEq` for `Content` and documented it, as it is
+//   re-exported publicly as `util::Value`
+// - allowed dead code for the by-reference deserializers, which are currently
+//   unused
+// - changed visibility of `Content::unexpected` to crate-public
 //
 
 
 pub mod size_hint {
-    use std::cmp;
+    use core::cmp;
 
     pub fn from_bounds<I>(iter: &I) -> Option<usize>
     where
@@ -92,8 +97,12 @@ pub mod size_hint {
 }
 
 
-use std::fmt;
-use std::marker::PhantomData;
+use core::fmt;
+use core::marker::PhantomData;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use serde::de::{
     self,
@@ -106,40 +115,68 @@ use serde::de::{
     Visitor,
 };
 
-#[derive(Debug)]
+/// A buffered value of Serde's data model.
+///
+/// Values of this type are created by deserializing any self-describing data
+/// format into it. Strings and byte-arrays are borrowed from the input if the
+/// deserializer allows this.
+#[derive(Clone, Debug, PartialEq)]
 pub enum Content<'de> {
+    /// A `bool`.
     Bool(bool),
 
+    /// An `u8`.
     U8(u8),
+    /// An `u16`.
     U16(u16),
+    /// An `u32`.
     U32(u32),
+    /// An `u64`.
     U64(u64),
 
+    /// An `i8`.
     I8(i8),
+    /// An `i16`.
     I16(i16),
+    /// An `i32`.
     I32(i32),
+    /// An `i64`.
     I64(i64),
 
+    /// An `f32`.
     F32(f32),
+    /// An `f64`.
     F64(f64),
 
+    /// A `char`.
     Char(char),
+    /// An owned string.
     String(String),
+    /// A string borrowed from the input.
     Str(&'de str),
+    /// An owned byte-array.
     ByteBuf(Vec<u8>),
+    /// A byte-array borrowed from the input.
     Bytes(&'de [u8]),
 
+    /// An absent optional value.
     None,
+    /// A present optional value.
     Some(Box<Content<'de>>),
 
+    /// A unit value, unit struct, or unit variant.
     Unit,
+    /// The value of a newtype struct.
     Newtype(Box<Content<'de>>),
+    /// A sequence, tuple, or tuple struct.
     Seq(Vec<Content<'de>>),
+    /// A map or struct, as list of key-value pairs in order of their
+    /// occurrence.
     Map(Vec<(Content<'de>, Content<'de>)>),
 }
 
 impl<'de> Content<'de> {
-    fn unexpected(&self) -> Unexpected {
+    pub(crate) fn unexpected(&self) -> Unexpected<'_> {
         match *self {
             Content::Bool(b) => Unexpected::Bool(b),
             Content::U8(n) => Unexpected::Unsigned(u64::from(n)),
@@ -804,6 +841,7 @@ where
 }
 
 
+#[allow(dead_code)]
 pub struct ContentRefDeserializer<'a, 'de: 'a, E> {
     content: &'a Content<'de>,
     err:     PhantomData<E>,
@@ -934,6 +972,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 impl<'a, 'de, E> ContentRefDeserializer<'a, 'de, E> {
     pub fn new(content: &'a Content<'de>) -> Self {
         ContentRefDeserializer {
@@ -943,6 +982,7 @@ impl<'a, 'de, E> ContentRefDeserializer<'a, 'de, E> {
     }
 }
 
+#[allow(dead_code)]
 struct EnumRefDeserializer<'a, 'de: 'a, E>
 where
     E: de::Error,
@@ -972,6 +1012,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 struct VariantRefDeserializer<'a, 'de: 'a, E>
 where
     E: de::Error,
@@ -1052,6 +1093,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 struct SeqRefDeserializer<'a, 'de: 'a, E>
 where
     E: de::Error,
@@ -1060,6 +1102,7 @@ where
     err:  PhantomData<E>,
 }
 
+#[allow(dead_code)]
 impl<'a, 'de, E> SeqRefDeserializer<'a, 'de, E>
 where
     E: de::Error,
@@ -1127,6 +1170,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 struct MapRefDeserializer<'a, 'de: 'a, E>
 where
     E: de::Error,
@@ -1136,6 +1180,7 @@ where
     err:   PhantomData<E>,
 }
 
+#[allow(dead_code)]
 impl<'a, 'de, E> MapRefDeserializer<'a, 'de, E>
 where
     E: de::Error,
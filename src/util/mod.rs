@@ -6,9 +6,18 @@ pub mod erased;
 pub(crate) mod de;
 pub(crate) mod ser;
 
+#[cfg(feature = "std")]
+pub(crate) mod scope;
 
+/// A buffered value of serde's data model, e.g. used for payload migration.
+pub use self::de::content::Content as Value;
+
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+use core;
 use serde;
-use std;
 
 
 /// A type that can be used to as a potentially temporary string-based tag.
@@ -28,6 +37,22 @@ pub enum TagString<'a> {
     Borrowed(&'a str),
 }
 
+impl<'a> TagString<'a> {
+    /// Returns the sub-string in the given byte-range without allocating.
+    ///
+    /// Borrowed strings are sliced, owned strings are truncated in place.
+    pub(crate) fn into_slice(self, start: usize, end: usize) -> Self {
+        match self {
+            TagString::Borrowed(v) => TagString::Borrowed(&v[start..end]),
+            TagString::Owned(mut v) => {
+                v.truncate(end);
+                v.drain(..start);
+                TagString::Owned(v)
+            },
+        }
+    }
+}
+
 impl<'a> From<&'a str> for TagString<'a> {
     fn from(source: &'a str) -> Self {
         TagString::Borrowed(source)
@@ -40,25 +65,25 @@ impl<'a> From<String> for TagString<'a> {
     }
 }
 
-impl<'a> From<std::borrow::Cow<'a, str>> for TagString<'a> {
-    fn from(source: std::borrow::Cow<'a, str>) -> Self {
+impl<'a> From<alloc::borrow::Cow<'a, str>> for TagString<'a> {
+    fn from(source: alloc::borrow::Cow<'a, str>) -> Self {
         match source {
-            std::borrow::Cow::Owned(v) => TagString::Owned(v),
-            std::borrow::Cow::Borrowed(v) => TagString::Borrowed(v),
+            alloc::borrow::Cow::Owned(v) => TagString::Owned(v),
+            alloc::borrow::Cow::Borrowed(v) => TagString::Borrowed(v),
         }
     }
 }
 
-impl<'a> From<TagString<'a>> for std::borrow::Cow<'a, str> {
+impl<'a> From<TagString<'a>> for alloc::borrow::Cow<'a, str> {
     fn from(val: TagString<'a>) -> Self {
         match val {
-            TagString::Owned(v) => std::borrow::Cow::Owned(v),
-            TagString::Borrowed(v) => std::borrow::Cow::Borrowed(v),
+            TagString::Owned(v) => alloc::borrow::Cow::Owned(v),
+            TagString::Borrowed(v) => alloc::borrow::Cow::Borrowed(v),
         }
     }
 }
 
-impl<'a> std::ops::Deref for TagString<'a> {
+impl<'a> core::ops::Deref for TagString<'a> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -69,7 +94,7 @@ impl<'a> std::ops::Deref for TagString<'a> {
     }
 }
 
-impl<'a, B> std::cmp::PartialEq<B> for TagString<'a>
+impl<'a, B> core::cmp::PartialEq<B> for TagString<'a>
 where
     B: PartialEq<str>,
 {
@@ -78,43 +103,43 @@ where
     }
 }
 
-impl<'a, 'b> std::cmp::PartialEq<TagString<'b>> for TagString<'a> {
+impl<'a, 'b> core::cmp::PartialEq<TagString<'b>> for TagString<'a> {
     fn eq(&self, other: &TagString<'b>) -> bool {
         (**self).eq(&**other)
     }
 }
 
-impl<'a> std::cmp::Eq for TagString<'a> {}
+impl<'a> core::cmp::Eq for TagString<'a> {}
 
-impl<'a, B> std::cmp::PartialOrd<B> for TagString<'a>
+impl<'a, B> core::cmp::PartialOrd<B> for TagString<'a>
 where
     B: PartialOrd<str>,
 {
-    fn partial_cmp(&self, other: &B) -> Option<std::cmp::Ordering> {
-        other.partial_cmp(&**self).map(std::cmp::Ordering::reverse)
+    fn partial_cmp(&self, other: &B) -> Option<core::cmp::Ordering> {
+        other.partial_cmp(&**self).map(core::cmp::Ordering::reverse)
     }
 }
 
-impl<'a, 'b> std::cmp::PartialOrd<TagString<'b>> for TagString<'a> {
-    fn partial_cmp(&self, other: &TagString) -> Option<std::cmp::Ordering> {
+impl<'a, 'b> core::cmp::PartialOrd<TagString<'b>> for TagString<'a> {
+    fn partial_cmp(&self, other: &TagString) -> Option<core::cmp::Ordering> {
         (**self).partial_cmp(&**other)
     }
 }
 
-impl<'a> std::cmp::Ord for TagString<'a> {
-    fn cmp(&self, other: &TagString) -> std::cmp::Ordering {
+impl<'a> core::cmp::Ord for TagString<'a> {
+    fn cmp(&self, other: &TagString) -> core::cmp::Ordering {
         (**self).cmp(&**other)
     }
 }
 
-impl<'a> std::fmt::Display for TagString<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl<'a> core::fmt::Display for TagString<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         (**self).fmt(f)
     }
 }
 
-impl<'a> std::hash::Hash for TagString<'a> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl<'a> core::hash::Hash for TagString<'a> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         (**self).hash(state)
     }
 }
@@ -136,7 +161,7 @@ impl<'de> serde::Deserialize<'de> for TagString<'de> {
         impl<'de> serde::de::Visitor<'de> for Visitor {
             type Value = TagString<'de>;
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
                 write!(formatter, "any type of string")
             }
 
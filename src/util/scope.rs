@@ -0,0 +1,70 @@
+//! Thread-local stacks of scoped values.
+//!
+//! A scope makes a reference to a value available to all code executed within
+//! a closure on the current thread, without the need to pass it explicitly.
+//! This is used to provide registries to `Deserialize` implementations (and
+//! tag resolvers to `Serialize` implementations), which cannot receive any
+//! additional arguments.
+
+use core::any::TypeId;
+use core::cell::RefCell;
+
+use alloc::vec::Vec;
+
+use std::thread::LocalKey;
+
+
+/// A stack of scoped values, stored type-erased with their type.
+pub type Stack = RefCell<Vec<(TypeId, *const ())>>;
+
+
+/// Pops the top-most value from the stack when dropped, i.e. also when the
+/// scoped closure panics.
+struct Guard(&'static LocalKey<Stack>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.0.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+
+/// Pushes the given value onto the stack and executes the given closure,
+/// popping the value afterwards.
+pub fn enter<R, F, T>(stack: &'static LocalKey<Stack>, value: &R, func: F) -> T
+where
+    R: 'static,
+    F: FnOnce() -> T,
+{
+    let entry = (TypeId::of::<R>(), value as *const R as *const ());
+    stack.with(|stack| stack.borrow_mut().push(entry));
+
+    let _guard = Guard(stack);
+    func()
+}
+
+/// Executes the given closure with the inner-most value of type `R` on the
+/// stack, returning `None` if there is no such value.
+pub fn current<R, F, T>(stack: &'static LocalKey<Stack>, func: F) -> Option<T>
+where
+    R: 'static,
+    F: FnOnce(&R) -> T,
+{
+    let ptr = stack.with(|stack| {
+        let stack = stack.borrow();
+        let mut entries = stack.iter().rev();
+
+        entries
+            .find(|(id, _)| *id == TypeId::of::<R>())
+            .map(|(_, ptr)| *ptr as *const R)
+    })?;
+
+    // SAFETY: The pointer has been created from a reference of type `&R` in
+    // `enter`, which is only popped from the stack once `enter` returns (or
+    // unwinds). As the stack is thread-local and entries are popped in reverse
+    // order of being pushed, any entry on the stack refers to a value
+    // borrowed by a call to `enter` that is still active on this thread. The
+    // closure receives a reference with a lifetime bounded by this call, thus
+    // the reference cannot outlive the value.
+    Some(func(unsafe { &*ptr }))
+}
@@ -50,7 +50,12 @@
 // - changed visibility of types and constructor-functions to `pub`
 
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use serde::ser::{self, Serialize, Serializer};
 
@@ -5,8 +5,8 @@
 //! being called (e.g. in case of `serialize_bytes`) and can also be used to
 //! forward a serializer function-call to another serializer.
 
-use std::cell::Cell;
-use std::fmt::Display;
+use core::cell::Cell;
+use core::fmt::Display;
 
 use serde;
 
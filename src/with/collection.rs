@@ -0,0 +1,756 @@
+//! Serialization and deserialization of collections of tagged values.
+//!
+//! The functions in this module are generic over the tagging format, which is
+//! selected by one of the (private) format types of the modules in
+//! [`with`](crate::with). The public, format-specific modules (e.g.
+//! [`with::external::seq`](crate::with::external::seq)) are generated by the
+//! `collections` macro.
+
+use super::{TagRegistry, UnknownElement};
+use crate::de::SeedFactory;
+use crate::util::Value;
+
+use core;
+use core::marker::PhantomData;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde;
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess};
+use serde::ser::Serialize;
+
+
+/// Serialization of a single element in a specific tagging format.
+pub trait SerializeElement<T: ?Sized> {
+    fn serialize<S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer;
+}
+
+/// Deserialization of a single element in a specific tagging format.
+pub trait DeserializeElement<'de, T: TagRegistry<'de>> {
+    fn deserialize<D, F>(deserializer: D, seed_factory: F) -> Result<F::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        F: SeedFactory<'de, T::Tag>;
+}
+
+/// The result of deserializing a single element: Either the value, the
+/// element with unknown tag, or `None` if the element has been skipped without
+/// being reported (e.g. a reference to a skipped shared value).
+pub type ElementResult<'de, T> =
+    Result<T, Option<UnknownElement<'de, <T as TagRegistry<'de>>::Tag>>>;
+
+/// Deserialization of a single element, returning it as [`UnknownElement`]
+/// instead of failing if `lenient` is set and its tag is unknown.
+///
+/// This is implemented for all format types via the `collections` macro and
+/// allows wrapping formats (e.g. for shared values) to intercept the element.
+pub trait DeserializeLenient<'de, T: TagRegistry<'de>> {
+    fn deserialize_lenient<D>(
+        deserializer: D,
+        lenient: bool,
+    ) -> Result<ElementResult<'de, T>, D::Error>
+    where
+        D: serde::Deserializer<'de>;
+}
+
+/// Implements [`DeserializeLenient`] for a type implementing
+/// [`DeserializeElement`].
+pub fn deserialize_lenient<'de, Fmt, D, T>(
+    deserializer: D,
+    lenient: bool,
+) -> Result<ElementResult<'de, T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Fmt: DeserializeElement<'de, T>,
+    T: TagRegistry<'de>,
+    T::Tag: Clone,
+{
+    if lenient {
+        Fmt::deserialize(deserializer, Lenient(T::registry())).map(|value| value.map_err(Some))
+    } else {
+        Fmt::deserialize(deserializer, T::registry()).map(Ok)
+    }
+}
+
+
+/// A single element, serialized in the tagging format `Fmt`.
+pub struct Element<'a, Fmt, T: ?Sized> {
+    value:    &'a T,
+    _phantom: PhantomData<Fmt>,
+}
+
+impl<'a, Fmt, T: ?Sized> Element<'a, Fmt, T> {
+    pub fn new(value: &'a T) -> Self {
+        Element {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, Fmt, T> Serialize for Element<'a, Fmt, T>
+where
+    Fmt: SerializeElement<T>,
+    T: ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Fmt::serialize(self.value, serializer)
+    }
+}
+
+
+pub fn serialize_seq<Fmt, S, C, T>(values: &C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+    Fmt: SerializeElement<T>,
+    T: ?Sized,
+{
+    serializer.collect_seq(values.into_iter().map(Element::<Fmt, T>::new))
+}
+
+pub fn serialize_map<Fmt, S, C, K, T>(values: &C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    for<'a> &'a C: IntoIterator<Item = (&'a K, &'a T)>,
+    Fmt: SerializeElement<T>,
+    K: Serialize,
+    T: ?Sized,
+{
+    serializer.collect_map(
+        values
+            .into_iter()
+            .map(|(k, v)| (k, Element::<Fmt, T>::new(v))),
+    )
+}
+
+pub fn serialize_option<Fmt, S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    Fmt: SerializeElement<T>,
+{
+    match value {
+        Some(value) => serializer.serialize_some(&Element::<Fmt, T>::new(value)),
+        None => serializer.serialize_none(),
+    }
+}
+
+
+/// A [`SeedFactory`] adapter that creates a seed collecting the element
+/// instead of failing if the wrapped factory does not know the tag.
+///
+/// A tag is treated as unknown if the wrapped factory reports it via
+/// `Error::unknown_variant` or with the error message used by the
+/// seed-factories of this crate. All other errors are passed through.
+struct Lenient<F>(F);
+
+impl<'de, T, F> SeedFactory<'de, T> for Lenient<F>
+where
+    T: Clone,
+    F: SeedFactory<'de, T>,
+{
+    type Value = Result<F::Value, UnknownElement<'de, T>>;
+    type Seed = LenientSeed<F::Seed, T>;
+
+    fn seed<E>(self, tag: T) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        match self.0.seed::<LenientError>(tag.clone()) {
+            Ok(seed) => Ok(LenientSeed::Known(seed)),
+            Err(LenientError::UnknownTag) => Ok(LenientSeed::Unknown(tag)),
+            Err(LenientError::Other(msg)) => Err(E::custom(msg)),
+        }
+    }
+}
+
+/// The error type passed to the seed-factory wrapped by [`Lenient`],
+/// distinguishing unknown tags from other errors.
+#[derive(Debug)]
+enum LenientError {
+    UnknownTag,
+    Other(String),
+}
+
+impl core::fmt::Display for LenientError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            LenientError::UnknownTag => f.write_str("unknown tag"),
+            LenientError::Other(ref msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl serde::de::StdError for LenientError {}
+
+impl serde::de::Error for LenientError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        let msg = msg.to_string();
+
+        if crate::de::is_unknown_tag(&msg) {
+            LenientError::UnknownTag
+        } else {
+            LenientError::Other(msg)
+        }
+    }
+
+    fn unknown_variant(_variant: &str, _expected: &'static [&'static str]) -> Self {
+        LenientError::UnknownTag
+    }
+}
+
+enum LenientSeed<S, T> {
+    Known(S),
+    Unknown(T),
+}
+
+impl<'de, S, T> DeserializeSeed<'de> for LenientSeed<S, T>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = Result<S::Value, UnknownElement<'de, T>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::Deserialize;
+
+        match self {
+            LenientSeed::Known(seed) => seed.deserialize(deserializer).map(Ok),
+            LenientSeed::Unknown(tag) => {
+                let value = Value::deserialize(deserializer)?;
+                Ok(Err(UnknownElement { tag, value }))
+            },
+        }
+    }
+}
+
+
+/// A seed deserializing a single element in the tagging format `Fmt`.
+///
+/// If `lenient` is `false`, elements with an unknown tag result in the error
+/// reported by the seed-factory of the element type.
+pub struct ElementSeed<Fmt, T> {
+    lenient:  bool,
+    _phantom: PhantomData<(Fmt, T)>,
+}
+
+impl<Fmt, T> ElementSeed<Fmt, T> {
+    pub fn new(lenient: bool) -> Self {
+        ElementSeed {
+            lenient,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Fmt, T> Clone for ElementSeed<Fmt, T> {
+    fn clone(&self) -> Self {
+        ElementSeed::new(self.lenient)
+    }
+}
+
+impl<'de, Fmt, T> DeserializeSeed<'de> for ElementSeed<Fmt, T>
+where
+    Fmt: DeserializeLenient<'de, T>,
+    T: TagRegistry<'de>,
+    T::Tag: Clone,
+{
+    type Value = ElementResult<'de, T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Fmt::deserialize_lenient(deserializer, self.lenient)
+    }
+}
+
+
+/// Deserializes a single element, failing if its tag is unknown.
+#[cfg(feature = "std")]
+pub fn deserialize_element<'de, Fmt, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Fmt: DeserializeLenient<'de, T>,
+    T: TagRegistry<'de>,
+{
+    match Fmt::deserialize_lenient(deserializer, false)? {
+        Ok(value) => Ok(value),
+        Err(_) => Err(serde::de::Error::custom("unknown tag")),
+    }
+}
+
+
+type Unknowns<'de, T> = Vec<UnknownElement<'de, <T as TagRegistry<'de>>::Tag>>;
+
+pub fn deserialize_seq<'de, Fmt, D, C, T>(
+    deserializer: D,
+    lenient: bool,
+) -> Result<(C, Unknowns<'de, T>), D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Fmt: DeserializeLenient<'de, T>,
+    C: Default + Extend<T>,
+    T: TagRegistry<'de>,
+    T::Tag: Clone,
+{
+    struct SeqVisitor<Fmt, C, T> {
+        seed:     ElementSeed<Fmt, T>,
+        _phantom: PhantomData<C>,
+    }
+
+    impl<'de, Fmt, C, T> serde::de::Visitor<'de> for SeqVisitor<Fmt, C, T>
+    where
+        Fmt: DeserializeLenient<'de, T>,
+        C: Default + Extend<T>,
+        T: TagRegistry<'de>,
+        T::Tag: Clone,
+    {
+        type Value = (C, Unknowns<'de, T>);
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a sequence of tagged values")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut values = C::default();
+            let mut unknown = Vec::new();
+
+            while let Some(value) = seq.next_element_seed(self.seed.clone())? {
+                match value {
+                    Ok(value) => values.extend(Some(value)),
+                    Err(Some(element)) => unknown.push(element),
+                    Err(None) => {},
+                }
+            }
+
+            Ok((values, unknown))
+        }
+    }
+
+    deserializer.deserialize_seq(SeqVisitor {
+        seed:     ElementSeed::<Fmt, T>::new(lenient),
+        _phantom: PhantomData,
+    })
+}
+
+#[allow(clippy::type_complexity)]
+pub fn deserialize_map<'de, Fmt, D, C, K, T>(
+    deserializer: D,
+    lenient: bool,
+) -> Result<(C, Vec<(K, UnknownElement<'de, T::Tag>)>), D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Fmt: DeserializeLenient<'de, T>,
+    C: Default + Extend<(K, T)>,
+    K: serde::Deserialize<'de>,
+    T: TagRegistry<'de>,
+    T::Tag: Clone,
+{
+    struct MapVisitor<Fmt, C, K, T> {
+        seed:     ElementSeed<Fmt, T>,
+        _phantom: PhantomData<(C, K)>,
+    }
+
+    impl<'de, Fmt, C, K, T> serde::de::Visitor<'de> for MapVisitor<Fmt, C, K, T>
+    where
+        Fmt: DeserializeLenient<'de, T>,
+        C: Default + Extend<(K, T)>,
+        K: serde::Deserialize<'de>,
+        T: TagRegistry<'de>,
+        T::Tag: Clone,
+    {
+        type Value = (C, Vec<(K, UnknownElement<'de, T::Tag>)>);
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a map of tagged values")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut values = C::default();
+            let mut unknown = Vec::new();
+
+            while let Some(key) = map.next_key()? {
+                match map.next_value_seed(self.seed.clone())? {
+                    Ok(value) => values.extend(Some((key, value))),
+                    Err(Some(element)) => unknown.push((key, element)),
+                    Err(None) => {},
+                }
+            }
+
+            Ok((values, unknown))
+        }
+    }
+
+    deserializer.deserialize_map(MapVisitor {
+        seed:     ElementSeed::<Fmt, T>::new(lenient),
+        _phantom: PhantomData,
+    })
+}
+
+#[allow(clippy::type_complexity)]
+pub fn deserialize_option<'de, Fmt, D, T>(
+    deserializer: D,
+    lenient: bool,
+) -> Result<(Option<T>, Option<UnknownElement<'de, T::Tag>>), D::Error>
+where
+    D: serde::Deserializer<'de>,
+    Fmt: DeserializeLenient<'de, T>,
+    T: TagRegistry<'de>,
+    T::Tag: Clone,
+{
+    struct OptionVisitor<Fmt, T> {
+        seed: ElementSeed<Fmt, T>,
+    }
+
+    impl<'de, Fmt, T> serde::de::Visitor<'de> for OptionVisitor<Fmt, T>
+    where
+        Fmt: DeserializeLenient<'de, T>,
+        T: TagRegistry<'de>,
+        T::Tag: Clone,
+    {
+        type Value = (Option<T>, Option<UnknownElement<'de, T::Tag>>);
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("an optional tagged value")
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok((None, None))
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok((None, None))
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            match self.seed.deserialize(deserializer)? {
+                Ok(value) => Ok((Some(value), None)),
+                Err(element) => Ok((None, element)),
+            }
+        }
+    }
+
+    deserializer.deserialize_option(OptionVisitor {
+        seed: ElementSeed::<Fmt, T>::new(lenient),
+    })
+}
+
+
+/// Generates the private `Format` type of a tagging format module, which
+/// serializes elements via the `serialize` function of the module and
+/// deserializes them via the given block, with the deserializer and the seed
+/// factory bound to the given names. The optional argument specifies
+/// additional bounds required for the element type `T`.
+macro_rules! element_format {
+    ($(+ $bound:ident)* => |$deserializer:ident, $seed_factory:ident| $deserialize:block) => {
+        /// The tagging format of this module, used for collections.
+        struct Format;
+
+        impl<T> crate::with::collection::SerializeElement<T> for Format
+        where
+            T: crate::with::TagProvider $(+ $bound)* + ?Sized,
+        {
+            fn serialize<S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serialize(value, serializer)
+            }
+        }
+
+        impl<'de, T> crate::with::collection::DeserializeElement<'de, T> for Format
+        where
+            T: crate::with::TagRegistry<'de> $(+ $bound)*,
+        {
+            fn deserialize<D, F>($deserializer: D, $seed_factory: F) -> Result<F::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                F: crate::de::SeedFactory<'de, T::Tag>,
+            $deserialize
+        }
+    };
+
+    (|$deserializer:ident, $seed_factory:ident| $deserialize:block) => {
+        element_format!(=> |$deserializer, $seed_factory| $deserialize);
+    };
+}
+
+
+/// Generates the collection modules (`seq`, `map`, `option`) for a tagging
+/// format module, which is required to contain a private `Format` type
+/// implementing `SerializeElement` and `DeserializeElement`. The optional
+/// argument specifies additional bounds required for the element type.
+///
+/// The `@modules` variant only generates the modules and requires `Format` to
+/// implement `SerializeElement` and `DeserializeLenient` instead.
+macro_rules! collections {
+    (@modules $($bounds:tt)*) => {
+        /// Serialization and deserialization of sequences of tagged values
+        /// (e.g. `Vec<T>`).
+        ///
+        /// Elements with unknown tags are handled according to
+        /// [`TagRegistry::UNKNOWN`](crate::with::TagRegistry::UNKNOWN).
+        pub mod seq {
+            use super::Format;
+            use crate::with::collection;
+            use crate::with::{TagProvider, TagRegistry, UnknownElement, Unknown};
+            #[allow(unused_imports)]
+            use crate::with::TagKeys;
+
+            use alloc::vec::Vec;
+
+            use serde;
+
+
+            /// Serializes each element of the given sequence as tagged value.
+            pub fn serialize<S, C, T>(values: &C, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a C: IntoIterator<Item = &'a T>,
+                T: TagProvider $($bounds)* + ?Sized,
+            {
+                collection::serialize_seq::<Format, _, _, _>(values, serializer)
+            }
+
+            /// Deserializes a sequence of tagged values.
+            pub fn deserialize<'de, D, C, T>(deserializer: D) -> Result<C, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: Default + Extend<T>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                let lenient = T::UNKNOWN == Unknown::Skip;
+                collection::deserialize_seq::<Format, _, _, _>(deserializer, lenient)
+                    .map(|(values, _)| values)
+            }
+
+            /// Deserializes a sequence of tagged values, collecting elements
+            /// with unknown tags instead of failing.
+            #[allow(clippy::type_complexity)]
+            pub fn deserialize_collect<'de, D, C, T>(
+                deserializer: D,
+            ) -> Result<(C, Vec<UnknownElement<'de, T::Tag>>), D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: Default + Extend<T>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                collection::deserialize_seq::<Format, _, _, _>(deserializer, true)
+            }
+        }
+
+
+        /// Serialization and deserialization of maps with tagged values
+        /// (e.g. `HashMap<String, T>`).
+        ///
+        /// Entries with unknown tags are handled according to
+        /// [`TagRegistry::UNKNOWN`](crate::with::TagRegistry::UNKNOWN).
+        pub mod map {
+            use super::Format;
+            use crate::with::collection;
+            use crate::with::{TagProvider, TagRegistry, UnknownElement, Unknown};
+            #[allow(unused_imports)]
+            use crate::with::TagKeys;
+
+            use alloc::vec::Vec;
+
+            use serde;
+
+
+            /// Serializes each value of the given map as tagged value.
+            pub fn serialize<S, C, K, T>(values: &C, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                for<'a> &'a C: IntoIterator<Item = (&'a K, &'a T)>,
+                K: serde::Serialize,
+                T: TagProvider $($bounds)* + ?Sized,
+            {
+                collection::serialize_map::<Format, _, _, _, _>(values, serializer)
+            }
+
+            /// Deserializes a map with tagged values.
+            pub fn deserialize<'de, D, C, K, T>(deserializer: D) -> Result<C, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: Default + Extend<(K, T)>,
+                K: serde::Deserialize<'de>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                let lenient = T::UNKNOWN == Unknown::Skip;
+                collection::deserialize_map::<Format, _, _, _, _>(deserializer, lenient)
+                    .map(|(values, _)| values)
+            }
+
+            /// Deserializes a map with tagged values, collecting entries with
+            /// unknown tags instead of failing.
+            #[allow(clippy::type_complexity)]
+            pub fn deserialize_collect<'de, D, C, K, T>(
+                deserializer: D,
+            ) -> Result<(C, Vec<(K, UnknownElement<'de, T::Tag>)>), D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: Default + Extend<(K, T)>,
+                K: serde::Deserialize<'de>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                collection::deserialize_map::<Format, _, _, _, _>(deserializer, true)
+            }
+        }
+
+
+        /// Serialization and deserialization of optional tagged values.
+        ///
+        /// Values with unknown tags are handled according to
+        /// [`TagRegistry::UNKNOWN`](crate::with::TagRegistry::UNKNOWN), i.e.
+        /// deserialized as `None` if they should be skipped.
+        pub mod option {
+            use super::Format;
+            use crate::with::collection;
+            use crate::with::{TagProvider, TagRegistry, UnknownElement, Unknown};
+            #[allow(unused_imports)]
+            use crate::with::TagKeys;
+
+            use serde;
+
+
+            /// Serializes the given value as tagged value, if present.
+            pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                T: TagProvider $($bounds)*,
+            {
+                collection::serialize_option::<Format, _, _>(value, serializer)
+            }
+
+            /// Deserializes an optional tagged value.
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                let lenient = T::UNKNOWN == Unknown::Skip;
+                collection::deserialize_option::<Format, _, _>(deserializer, lenient)
+                    .map(|(value, _)| value)
+            }
+
+            /// Deserializes an optional tagged value, returning the value with
+            /// unknown tag instead of failing.
+            #[allow(clippy::type_complexity)]
+            pub fn deserialize_collect<'de, D, T>(
+                deserializer: D,
+            ) -> Result<(Option<T>, Option<UnknownElement<'de, T::Tag>>), D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                T: TagRegistry<'de> $($bounds)*,
+                T::Tag: Clone,
+            {
+                collection::deserialize_option::<Format, _, _>(deserializer, true)
+            }
+        }
+    };
+
+    ($($bounds:tt)*) => {
+        impl<'de, T> crate::with::collection::DeserializeLenient<'de, T> for Format
+        where
+            T: crate::with::TagRegistry<'de> $($bounds)*,
+            T::Tag: Clone,
+        {
+            fn deserialize_lenient<D>(
+                deserializer: D,
+                lenient: bool,
+            ) -> Result<crate::with::collection::ElementResult<'de, T>, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                crate::with::collection::deserialize_lenient::<Self, _, _>(deserializer, lenient)
+            }
+        }
+
+        collections!(@modules $($bounds)*);
+    };
+}
+
+
+/// Generates the `shared` module for a tagging format module, which is
+/// required to contain a private `Format` type implementing `SerializeElement`
+/// and `DeserializeElement`. The shared module in turn contains the collection
+/// modules for shared values. The optional argument specifies additional
+/// bounds required for the element type.
+macro_rules! shared {
+    ($($bounds:tt)*) => {
+        /// Serialization and deserialization of shared values (e.g. `Rc<T>`),
+        /// restoring the sharing on deserialization.
+        ///
+        /// See [`with::share`](crate::with::share) for details.
+        #[cfg(feature = "std")]
+        pub mod shared {
+            use crate::with::collection::{self, SerializeElement};
+            use crate::with::share::{Share, Shared};
+            use crate::with::{TagProvider, TagRegistry};
+            #[allow(unused_imports)]
+            use crate::with::TagKeys;
+
+            use serde;
+
+
+            /// Serializes the given shared value as tagged value, or as
+            /// reference if it has been serialized before in the current
+            /// sharing scope.
+            pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                T: TagProvider + Share $($bounds)*,
+            {
+                Format::serialize(value, serializer)
+            }
+
+            /// Deserializes a shared tagged value or a reference to a value
+            /// deserialized before in the current sharing scope.
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                T: TagRegistry<'de> + Share $($bounds)*,
+                T::Tag: Clone,
+            {
+                collection::deserialize_element::<Format, _, _>(deserializer)
+            }
+
+
+            collections!(@modules + crate::with::share::Share $($bounds)*);
+
+
+            /// The tagging format of the parent module, extended for shared
+            /// values.
+            type Format = Shared<super::Format>;
+        }
+    };
+}
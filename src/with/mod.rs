@@ -0,0 +1,405 @@
+//! Modules for use with `#[serde(with = "...")]`.
+//!
+//! Each module of this namespace provides a `serialize` and a `deserialize`
+//! function for one of the tagging formats, which allows tagging of struct
+//! fields without writing a wrapper type. The type of the field specifies how
+//! it is tagged by implementing
+//!
+//! - [`TagProvider`], which provides the tag and the value to serialize,
+//! - [`TagRegistry`], which provides the seed-factory used to deserialize the
+//!   value based on its tag, and
+//! - [`TagKeys`], which provides the keys (and the struct name) used by the
+//!   [`internal`], [`adj_map`] and [`adj_struct`] formats.
+//!
+//! Each format module further contains the `seq`, `map` and `option`
+//! submodules for collections of tagged values, e.g. `Vec<Box<dyn Trait>>`.
+//! Elements with unknown tags are handled according to
+//! [`TagRegistry::UNKNOWN`], or can be collected via the
+//! `deserialize_collect` functions of these modules.
+//!
+//! With the `std` feature, each format module also contains a `shared`
+//! submodule, which preserves the sharing of `Rc` and `Arc` pointers (see
+//! [`share`]).
+//!
+//! # Example
+//!
+//! ```
+//! # extern crate serde;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_json;
+//! # extern crate serde_tagged;
+//! #
+//! use serde_tagged::de::WithoutTag;
+//! use serde_tagged::with::{TagKeys, TagProvider, TagRegistry};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! impl TagProvider for Point {
+//!     type Tag = str;
+//!     type Value = Self;
+//!
+//!     fn tag(&self) -> &str {
+//!         "point"
+//!     }
+//!
+//!     fn value(&self) -> &Self {
+//!         self
+//!     }
+//! }
+//!
+//! impl<'de> TagRegistry<'de> for Point {
+//!     type Tag = String;
+//!     type Factory = WithoutTag<Point>;
+//!
+//!     fn registry() -> Self::Factory {
+//!         WithoutTag::new()
+//!     }
+//! }
+//!
+//! impl TagKeys for Point {}
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Shape {
+//!     #[serde(with = "serde_tagged::with::adj_map")]
+//!     origin: Point,
+//! }
+//!
+//! # fn main() {
+//! let shape = Shape { origin: Point { x: 1, y: 2 } };
+//!
+//! let json = serde_json::to_string(&shape).unwrap();
+//! assert_eq!(json, r#"{"origin":{"type":"point","value":{"x":1,"y":2}}}"#);
+//!
+//! let de: Shape = serde_json::from_str(&json).unwrap();
+//! assert_eq!(de, shape);
+//! # }
+//! ```
+
+use crate::de::SeedFactory;
+use crate::util::Value;
+
+use serde;
+
+pub use crate::format::TagKeys;
+
+
+#[macro_use]
+mod collection;
+
+#[cfg(feature = "std")]
+pub mod share;
+
+
+/// A type providing the tag (and the value) to serialize.
+pub trait TagProvider {
+    /// The type of the tag.
+    type Tag: serde::Serialize + ?Sized;
+
+    /// The type of the value serialized alongside the tag.
+    type Value: serde::Serialize + ?Sized;
+
+    /// Returns the tag of this value.
+    fn tag(&self) -> &Self::Tag;
+
+    /// Returns the value to serialize alongside the tag.
+    ///
+    /// For trait objects, this is typically the trait object itself, erased
+    /// via `erased_serde`.
+    fn value(&self) -> &Self::Value;
+}
+
+
+/// A type providing the seed-factory used to deserialize it based on its tag.
+pub trait TagRegistry<'de>: Sized {
+    /// The type of the tag.
+    type Tag: serde::Deserialize<'de>;
+
+    /// The seed-factory creating the seeds to deserialize values of this type.
+    type Factory: SeedFactory<'de, Self::Tag, Value = Self>;
+
+    /// The policy for elements with unknown tags in collections (see e.g.
+    /// [`external::seq`]).
+    const UNKNOWN: Unknown = Unknown::Error;
+
+    /// Returns the seed-factory to deserialize values of this type, e.g. a
+    /// reference to a static registry.
+    fn registry() -> Self::Factory;
+}
+
+
+/// The policy for elements with unknown tags in collections.
+///
+/// The seed-factory of the element type decides whether a tag is known. A tag
+/// is treated as unknown if the seed-factory reports it via
+/// `Error::unknown_variant` or in the same way as the seed-factories provided
+/// by this crate (i.e. with the message ``unknown tag `<tag>` ``). Any other
+/// error returned by the seed-factory (e.g. for a malformed versioned tag) is
+/// passed through and fails deserialization regardless of the policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unknown {
+    /// Fail deserialization of the whole collection.
+    Error,
+
+    /// Skip the element.
+    Skip,
+}
+
+
+/// An element of a collection with unknown tag.
+///
+/// Such elements are returned by the `deserialize_collect` functions of the
+/// collection modules (e.g. [`external::seq::deserialize_collect`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnknownElement<'de, T> {
+    /// The tag of the element.
+    pub tag: T,
+
+    /// The buffered value of the element.
+    pub value: Value<'de>,
+}
+
+
+/// Serialization and deserialization of externally tagged values.
+///
+/// See [`ser::external`](crate::ser::external) for a description of this
+/// tagging format.
+pub mod external {
+    use super::{TagProvider, TagRegistry};
+
+    use serde;
+
+
+    /// Serializes the given value as externally tagged value.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: TagProvider + ?Sized,
+    {
+        crate::ser::external::serialize(serializer, value.tag(), value.value())
+    }
+
+    /// Deserializes an externally tagged value.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: TagRegistry<'de>,
+    {
+        crate::de::external::deserialize(deserializer, T::registry())
+    }
+
+
+    collections!();
+    shared!();
+
+
+    element_format!(|deserializer, seed_factory| {
+        crate::de::external::deserialize(deserializer, seed_factory)
+    });
+}
+
+
+/// Serialization and deserialization of internally tagged values.
+///
+/// See [`ser::internal`](crate::ser::internal) for a description of this
+/// tagging format.
+pub mod internal {
+    use super::{TagKeys, TagProvider, TagRegistry};
+
+    use serde;
+
+
+    /// Serializes the given value as internally tagged value, using
+    /// [`TagKeys::TAG_KEY`] as key for the tag.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: TagProvider + TagKeys + ?Sized,
+    {
+        crate::ser::internal::serialize(serializer, T::TAG_KEY, value.tag(), value.value())
+    }
+
+    /// Deserializes an internally tagged value, using [`TagKeys::TAG_KEY`] as
+    /// key for the tag.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: TagRegistry<'de> + TagKeys,
+    {
+        crate::de::internal::deserialize(deserializer, T::TAG_KEY, T::registry())
+    }
+
+
+    collections!(+ TagKeys);
+    shared!(+ TagKeys);
+
+
+    element_format!(+ TagKeys => |deserializer, seed_factory| {
+        crate::de::internal::deserialize(deserializer, T::TAG_KEY, seed_factory)
+    });
+}
+
+
+/// Serialization and deserialization of adjacently tagged values using tuples.
+///
+/// See [`ser::adj::tuple`](crate::ser::adj::tuple) for a description of this
+/// tagging format.
+pub mod adj_tuple {
+    use super::{TagProvider, TagRegistry};
+
+    use serde;
+
+
+    /// Serializes the given value as tuple-based adjacently tagged value.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: TagProvider + ?Sized,
+    {
+        crate::ser::adj::tuple::serialize(serializer, value.tag(), value.value())
+    }
+
+    /// Deserializes a tuple-based adjacently tagged value.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: TagRegistry<'de>,
+    {
+        crate::de::adj::tuple::deserialize(deserializer, T::registry())
+    }
+
+
+    collections!();
+    shared!();
+
+
+    element_format!(|deserializer, seed_factory| {
+        crate::de::adj::tuple::deserialize(deserializer, seed_factory)
+    });
+}
+
+
+/// Serialization and deserialization of adjacently tagged values using maps.
+///
+/// See [`ser::adj::map`](crate::ser::adj::map) for a description of this
+/// tagging format.
+pub mod adj_map {
+    use super::{TagKeys, TagProvider, TagRegistry};
+
+    use alloc::string::String;
+
+    use serde;
+
+
+    /// Serializes the given value as map-based adjacently tagged value, using
+    /// [`TagKeys::TAG_KEY`] and [`TagKeys::VALUE_KEY`] as keys.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: TagProvider + TagKeys + ?Sized,
+    {
+        crate::ser::adj::map::serialize(
+            serializer,
+            T::TAG_KEY,
+            value.tag(),
+            T::VALUE_KEY,
+            value.value(),
+        )
+    }
+
+    /// Deserializes a map-based adjacently tagged value, using
+    /// [`TagKeys::TAG_KEY`] and [`TagKeys::VALUE_KEY`] as keys.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: TagRegistry<'de> + TagKeys,
+    {
+        crate::de::adj::map::deserialize::<T::Tag, String, str, _, _>(
+            deserializer,
+            T::TAG_KEY,
+            T::VALUE_KEY,
+            T::registry(),
+        )
+    }
+
+
+    collections!(+ TagKeys);
+    shared!(+ TagKeys);
+
+
+    element_format!(+ TagKeys => |deserializer, seed_factory| {
+        crate::de::adj::map::deserialize::<T::Tag, String, str, _, _>(
+            deserializer,
+            T::TAG_KEY,
+            T::VALUE_KEY,
+            seed_factory,
+        )
+    });
+}
+
+
+/// Serialization and deserialization of adjacently tagged values using
+/// structs.
+///
+/// See [`ser::adj::struc`](crate::ser::adj::struc) for a description of this
+/// tagging format.
+pub mod adj_struct {
+    use super::{TagKeys, TagProvider, TagRegistry};
+
+    use serde;
+
+
+    /// Serializes the given value as struct-based adjacently tagged value,
+    /// using [`TagKeys::NAME`], [`TagKeys::TAG_KEY`] and
+    /// [`TagKeys::VALUE_KEY`] as struct name and field names.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: TagProvider + TagKeys + ?Sized,
+    {
+        crate::ser::adj::struc::serialize(
+            serializer,
+            T::NAME,
+            T::TAG_KEY,
+            value.tag(),
+            T::VALUE_KEY,
+            value.value(),
+        )
+    }
+
+    /// Deserializes a struct-based adjacently tagged value, using
+    /// [`TagKeys::NAME`], [`TagKeys::TAG_KEY`] and [`TagKeys::VALUE_KEY`] as
+    /// struct name and field names.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: TagRegistry<'de> + TagKeys,
+    {
+        crate::de::adj::struc::deserialize(
+            deserializer,
+            T::NAME,
+            T::TAG_KEY,
+            T::VALUE_KEY,
+            T::registry(),
+        )
+    }
+
+
+    collections!(+ TagKeys);
+    shared!(+ TagKeys);
+
+
+    element_format!(+ TagKeys => |deserializer, seed_factory| {
+        crate::de::adj::struc::deserialize(
+            deserializer,
+            T::NAME,
+            T::TAG_KEY,
+            T::VALUE_KEY,
+            seed_factory,
+        )
+    });
+}
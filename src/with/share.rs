@@ -0,0 +1,465 @@
+//! Serialization and deserialization of shared values (e.g. `Rc<dyn Trait>`).
+//!
+//! By default, a value behind an `Rc` or `Arc` is serialized each time the
+//! pointer is encountered and deserialized into independent copies. The
+//! `shared` modules of the tagging formats in [`with`](crate::with) (e.g.
+//! [`with::external::shared`](crate::with::external::shared)) instead assign
+//! an id to each shared value on its first occurrence and serialize any
+//! further occurrence as reference to it. On deserialization, references are
+//! resolved to clones of the same pointer, thus restoring the sharing.
+//!
+//! The first occurrence of a value is serialized as map containing its id
+//! and the tagged value, any further occurrence as map containing only the
+//! id, i.e. in JSON:
+//!
+//! ```json
+//! [
+//!     { "$id": 0, "$value": { "rect": { "w": 1.0, "h": 2.0 } } },
+//!     { "$ref": 0 }
+//! ]
+//! ```
+//!
+//! Ids are only valid inside a sharing [`scope`], which has to enclose the
+//! whole serialization or deserialization. Serializing or deserializing a
+//! shared value outside of a scope results in an error. A value is only
+//! referenced by later occurrences if its first occurrence has been
+//! serialized successfully.
+//!
+//! If elements with unknown tags are skipped (see
+//! [`TagRegistry::UNKNOWN`]), references to a skipped value are skipped as
+//! well. The `deserialize_collect` functions only report the first occurrence
+//! of such a value.
+//!
+//! As `Rc` and `Arc` cannot be extended by other crates, this module provides
+//! implementations of [`TagProvider`] and [`TagKeys`] forwarding to the
+//! pointee (e.g. `dyn Trait`), and of [`TagRegistry`] converting values
+//! deserialized via the implementation for `Box<T>` (e.g. `Box<dyn Trait>`).
+
+use super::collection::{
+    DeserializeLenient,
+    Element,
+    ElementResult,
+    ElementSeed,
+    SerializeElement,
+};
+use super::{TagKeys, TagProvider, TagRegistry, Unknown};
+use crate::de::SeedFactory;
+
+use core::any::Any;
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+
+use std::collections::HashMap;
+
+use serde;
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+
+
+const KEY_ID: &str = "$id";
+const KEY_REF: &str = "$ref";
+const KEY_VALUE: &str = "$value";
+
+
+/// A pointer type whose pointee can be shared, e.g. `Rc<T>` or `Arc<T>`.
+///
+/// Two pointers are considered to share their value if [`Share::as_ptr`]
+/// returns the same address for both of them.
+pub trait Share: Clone + 'static {
+    /// Returns the address of the shared value.
+    fn as_ptr(this: &Self) -> *const ();
+}
+
+impl<T: ?Sized + 'static> Share for Rc<T> {
+    fn as_ptr(this: &Self) -> *const () {
+        Rc::as_ptr(this) as *const ()
+    }
+}
+
+impl<T: ?Sized + 'static> Share for Arc<T> {
+    fn as_ptr(this: &Self) -> *const () {
+        Arc::as_ptr(this) as *const ()
+    }
+}
+
+
+macro_rules! impl_pointer {
+    ($ptr:ident) => {
+        impl<T> TagProvider for $ptr<T>
+        where
+            T: TagProvider + ?Sized,
+        {
+            type Tag = T::Tag;
+            type Value = T::Value;
+
+            fn tag(&self) -> &Self::Tag {
+                (**self).tag()
+            }
+
+            fn value(&self) -> &Self::Value {
+                (**self).value()
+            }
+        }
+
+        impl<'de, T> TagRegistry<'de> for $ptr<T>
+        where
+            T: ?Sized,
+            Box<T>: TagRegistry<'de>,
+        {
+            type Tag = <Box<T> as TagRegistry<'de>>::Tag;
+            type Factory = Convert<<Box<T> as TagRegistry<'de>>::Factory, Self>;
+
+            const UNKNOWN: Unknown = <Box<T> as TagRegistry<'de>>::UNKNOWN;
+
+            fn registry() -> Self::Factory {
+                Convert::new(<Box<T> as TagRegistry<'de>>::registry())
+            }
+        }
+
+        impl<T> TagKeys for $ptr<T>
+        where
+            T: TagKeys + ?Sized,
+        {
+            const TAG_KEY: &'static str = T::TAG_KEY;
+            const VALUE_KEY: &'static str = T::VALUE_KEY;
+            const NAME: &'static str = T::NAME;
+        }
+    };
+}
+
+impl_pointer!(Rc);
+impl_pointer!(Arc);
+
+
+/// A [`SeedFactory`] adapter converting the values produced by the wrapped
+/// factory via `From`, e.g. from `Box<T>` to `Rc<T>`.
+pub struct Convert<F, P> {
+    factory:  F,
+    _phantom: PhantomData<P>,
+}
+
+impl<F, P> Convert<F, P> {
+    /// Creates a new adapter for the given seed-factory.
+    pub fn new(factory: F) -> Self {
+        Convert {
+            factory,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, F, P> SeedFactory<'de, T> for Convert<F, P>
+where
+    F: SeedFactory<'de, T>,
+    P: From<F::Value>,
+{
+    type Value = P;
+    type Seed = Convert<F::Seed, P>;
+
+    fn seed<E>(self, tag: T) -> Result<Self::Seed, E>
+    where
+        E: serde::de::Error,
+    {
+        self.factory.seed(tag).map(Convert::new)
+    }
+}
+
+impl<'de, S, P> DeserializeSeed<'de> for Convert<S, P>
+where
+    S: DeserializeSeed<'de>,
+    P: From<S::Value>,
+{
+    type Value = P;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.factory.deserialize(deserializer).map(P::from)
+    }
+}
+
+
+/// The ids assigned to shared values in the current scope.
+#[derive(Default)]
+struct Table {
+    /// The ids of serialized values by address. The pointers are kept alive
+    /// to ensure that their addresses are not re-used inside the scope.
+    ser: HashMap<*const (), (u64, Box<dyn Any>)>,
+
+    /// The id assigned to the next newly serialized value.
+    next: u64,
+
+    /// The deserialized values by id, or `None` for values that have been
+    /// skipped due to an unknown tag.
+    de: HashMap<u64, Option<Box<dyn Any>>>,
+}
+
+std::thread_local! {
+    static TABLE: RefCell<Option<Table>> = const { RefCell::new(None) };
+}
+
+
+/// Executes the given closure inside a new sharing scope.
+///
+/// Shared values serialized or deserialized on the current thread during
+/// execution of the closure share the same ids. Scopes can be nested, in
+/// which case the inner scope starts with a new set of ids.
+pub fn scope<F, T>(func: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    struct Guard(Option<Table>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            TABLE.with(|table| *table.borrow_mut() = outer);
+        }
+    }
+
+    let outer = TABLE.with(|table| table.replace(Some(Table::default())));
+    let _guard = Guard(outer);
+
+    func()
+}
+
+
+/// Returns the id of the given value and whether it is new, or `None` if
+/// there is no scope.
+///
+/// New ids are reserved but only recorded for the value via [`ser_insert`],
+/// i.e. after the value has been serialized successfully.
+fn ser_id<T: Share>(value: &T) -> Option<(u64, bool)> {
+    TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+        let table = table.as_mut()?;
+
+        if let Some((id, _)) = table.ser.get(&Share::as_ptr(value)) {
+            return Some((*id, false));
+        }
+
+        let id = table.next;
+        table.next += 1;
+
+        Some((id, true))
+    })
+}
+
+/// Records the given id for the given, successfully serialized value.
+fn ser_insert<T: Share>(id: u64, value: &T) {
+    TABLE.with(|table| {
+        if let Some(table) = table.borrow_mut().as_mut() {
+            table
+                .ser
+                .insert(Share::as_ptr(value), (id, Box::new(value.clone())));
+        }
+    })
+}
+
+/// Returns whether there is a scope.
+fn in_scope() -> bool {
+    TABLE.with(|table| table.borrow().is_some())
+}
+
+/// Stores the value deserialized for the given id, or `None` if the value
+/// has been skipped. Returns `false` if the id already exists.
+fn de_insert<T: Share>(id: u64, value: Option<&T>) -> bool {
+    TABLE.with(|table| match table.borrow_mut().as_mut() {
+        Some(table) => {
+            let value = value.map(|value| Box::new(value.clone()) as Box<dyn Any>);
+            table.de.insert(id, value).is_none()
+        },
+        None => false,
+    })
+}
+
+/// Returns the value deserialized for the given id, `Some(None)` if the value
+/// has been skipped, or `None` if there is no value of the requested type.
+fn de_get<T: Share>(id: u64) -> Option<Option<T>> {
+    TABLE.with(|table| {
+        let table = table.borrow();
+        match table.as_ref()?.de.get(&id)? {
+            Some(value) => value.downcast_ref::<T>().cloned().map(Some),
+            None => Some(None),
+        }
+    })
+}
+
+
+/// The tagging format `Fmt`, extended by ids and references for shared
+/// values.
+pub struct Shared<Fmt> {
+    _phantom: PhantomData<Fmt>,
+}
+
+impl<Fmt, T> SerializeElement<T> for Shared<Fmt>
+where
+    Fmt: SerializeElement<T>,
+    T: Share,
+{
+    fn serialize<S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let (id, new) = match ser_id(value) {
+            Some(id) => id,
+            None => return Err(S::Error::custom("shared value outside of sharing scope")),
+        };
+
+        if new {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry(KEY_ID, &id)?;
+            map.serialize_entry(KEY_VALUE, &Element::<Fmt, T>::new(value))?;
+            let ok = map.end()?;
+
+            ser_insert(id, value);
+            Ok(ok)
+        } else {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(KEY_REF, &id)?;
+            map.end()
+        }
+    }
+}
+
+impl<'de, Fmt, T> DeserializeLenient<'de, T> for Shared<Fmt>
+where
+    Fmt: DeserializeLenient<'de, T>,
+    T: TagRegistry<'de> + Share,
+    T::Tag: Clone,
+{
+    fn deserialize_lenient<D>(
+        deserializer: D,
+        lenient: bool,
+    ) -> Result<ElementResult<'de, T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        if !in_scope() {
+            return Err(D::Error::custom("shared value outside of sharing scope"));
+        }
+
+        deserializer.deserialize_map(SharedVisitor::<Fmt, T> {
+            lenient,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+
+struct SharedVisitor<Fmt, T> {
+    lenient:  bool,
+    _phantom: PhantomData<(Fmt, T)>,
+}
+
+impl<'de, Fmt, T> Visitor<'de> for SharedVisitor<Fmt, T>
+where
+    Fmt: DeserializeLenient<'de, T>,
+    T: TagRegistry<'de> + Share,
+    T::Tag: Clone,
+{
+    type Value = ElementResult<'de, T>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a shared value or a reference to it")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let value = match map.next_key::<Key>()? {
+            Some(Key::Ref) => {
+                let id: u64 = map.next_value()?;
+
+                match de_get(id) {
+                    Some(Some(value)) => Ok(value),
+                    Some(None) => Err(None),
+                    None => {
+                        return Err(A::Error::custom(format_args!(
+                            "unknown shared value reference `{}`",
+                            id
+                        )));
+                    },
+                }
+            },
+            Some(Key::Id) => {
+                let id: u64 = map.next_value()?;
+
+                match map.next_key::<Key>()? {
+                    Some(Key::Value) => {},
+                    Some(_) => return Err(A::Error::custom("expected key `$value`")),
+                    None => return Err(A::Error::missing_field(KEY_VALUE)),
+                }
+
+                let value = map.next_value_seed(ElementSeed::<Fmt, T>::new(self.lenient))?;
+
+                if !de_insert(id, value.as_ref().ok()) {
+                    return Err(A::Error::custom(format_args!(
+                        "duplicate shared value id `{}`",
+                        id
+                    )));
+                }
+
+                value
+            },
+            Some(Key::Value) => return Err(A::Error::custom("expected key `$id` or `$ref`")),
+            None => return Err(A::Error::missing_field(KEY_ID)),
+        };
+
+        match map.next_key::<Key>()? {
+            None => Ok(value),
+            Some(_) => Err(A::Error::custom("unexpected key after shared value")),
+        }
+    }
+}
+
+
+/// The keys of a shared value.
+enum Key {
+    Id,
+    Ref,
+    Value,
+}
+
+impl<'de> serde::Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("`$id`, `$ref`, or `$value`")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Key, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    KEY_ID => Ok(Key::Id),
+                    KEY_REF => Ok(Key::Ref),
+                    KEY_VALUE => Ok(Key::Value),
+                    _ => Err(E::unknown_field(value, &[KEY_ID, KEY_REF, KEY_VALUE])),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(KeyVisitor)
+    }
+}
@@ -0,0 +1,154 @@
+//! Tests using `bincode`, a data format that is not self-describing.
+
+extern crate bincode;
+extern crate serde;
+extern crate serde_tagged;
+
+#[macro_use]
+extern crate serde_derive;
+
+
+/// Tests for values tagged as enum variants.
+mod variant {
+    use bincode::{DefaultOptions, Options};
+    use serde_tagged::de::{WithTag, WithoutTag};
+    use serde_tagged::format::Variants;
+    use serde_tagged::util::TagString;
+
+    const VARIANTS: Variants = Variants::new("Shape", &["circle", "rect"]);
+
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Rect {
+        width:  u32,
+        height: u32,
+    }
+
+    fn serialize<V: serde::Serialize>(tag: &str, value: &V) -> Result<Vec<u8>, bincode::Error> {
+        let mut out = Vec::new();
+        let mut ser = bincode::Serializer::new(&mut out, DefaultOptions::new());
+
+        serde_tagged::ser::variant::serialize(&mut ser, &VARIANTS, tag, value)?;
+        Ok(out)
+    }
+
+
+    #[test]
+    fn roundtrip() {
+        let rect = Rect {
+            width:  3,
+            height: 4,
+        };
+
+        let bytes = serialize("rect", &rect).unwrap();
+        assert_eq!(
+            bytes,
+            DefaultOptions::new().serialize(&(1_u32, &rect)).unwrap()
+        );
+
+        let mut de = bincode::Deserializer::from_slice(&bytes, DefaultOptions::new());
+        let (tag, value): (TagString, Rect) =
+            serde_tagged::de::variant::deserialize(&mut de, &VARIANTS, WithTag::new()).unwrap();
+
+        assert_eq!(&*tag, "rect");
+        assert_eq!(value, rect);
+    }
+
+    #[test]
+    fn roundtrip_sequence() {
+        let mut bytes = serialize("circle", &5_u32).unwrap();
+        bytes.extend(serialize("rect", &"foo").unwrap());
+
+        let mut de = bincode::Deserializer::from_slice(&bytes, DefaultOptions::new());
+
+        let (tag, radius): (TagString, u32) =
+            serde_tagged::de::variant::deserialize(&mut de, &VARIANTS, WithTag::new()).unwrap();
+        assert_eq!((&*tag, radius), ("circle", 5));
+
+        let (tag, name): (TagString, String) =
+            serde_tagged::de::variant::deserialize(&mut de, &VARIANTS, WithTag::new()).unwrap();
+        assert_eq!((&*tag, &*name), ("rect", "foo"));
+    }
+
+    #[test]
+    fn unknown_tag() {
+        let err = serialize("square", &42_u32).unwrap_err();
+        assert!(err.to_string().contains("unknown variant tag `square`"));
+    }
+
+    #[test]
+    fn unknown_index() {
+        let bytes = DefaultOptions::new().serialize(&(2_u32, 42_u32)).unwrap();
+
+        let mut de = bincode::Deserializer::from_slice(&bytes, DefaultOptions::new());
+        let result: Result<u32, _> =
+            serde_tagged::de::variant::deserialize(&mut de, &VARIANTS, WithoutTag::new());
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("a valid variant index"), "{}", err);
+    }
+}
+
+
+/// Tests for descriptions of tagging formats.
+mod format {
+    use bincode::{DefaultOptions, Options};
+    use serde_tagged::format::TagFormat;
+
+    #[derive(Serialize)]
+    struct Point {
+        x: u32,
+        y: u32,
+    }
+
+    /// Serializes a point via `ser::dynamic` in the given format.
+    fn serialize(format: &TagFormat) -> Result<Vec<u8>, bincode::Error> {
+        let mut out = Vec::new();
+        let mut ser = bincode::Serializer::new(&mut out, DefaultOptions::new());
+
+        let point = Point { x: 1, y: 2 };
+        serde_tagged::ser::dynamic::serialize(&mut ser, format, "p", &point)?;
+        Ok(out)
+    }
+
+    /// Returns the given format with owned names and keys.
+    fn owned(format: &TagFormat) -> TagFormat {
+        let bytes = DefaultOptions::new().serialize(format).unwrap();
+        DefaultOptions::new().deserialize(&bytes).unwrap()
+    }
+
+
+    #[test]
+    fn roundtrip() {
+        let formats = [
+            TagFormat::External,
+            TagFormat::internal("type"),
+            TagFormat::AdjTuple,
+            TagFormat::adj_map("t", "v"),
+            TagFormat::adj_struct("Tagged", "t", "v"),
+        ];
+
+        for format in formats {
+            let bytes = DefaultOptions::new().serialize(&format).unwrap();
+            let de: TagFormat = DefaultOptions::new().deserialize(&bytes).unwrap();
+
+            assert_eq!(de, format);
+        }
+    }
+
+    #[test]
+    fn owned_representation() {
+        for format in [TagFormat::internal("type"), TagFormat::adj_map("t", "v")] {
+            assert_eq!(
+                serialize(&owned(&format)).unwrap(),
+                serialize(&format).unwrap()
+            );
+        }
+
+        let format = TagFormat::adj_struct("Tagged", "t", "v");
+        assert!(serialize(&format).is_ok());
+
+        let err = serialize(&owned(&format)).unwrap_err();
+        assert!(err.to_string().contains("must be static"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Testing framework.
 
 pub mod formats;
+pub mod native;
 pub mod types;
 
 #[macro_use]
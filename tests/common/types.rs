@@ -234,3 +234,19 @@ impl<'a, D: Display + ?Sized> Serialize for CollectStr<'a, D> {
         serializer.collect_str(self.0)
     }
 }
+
+
+/// A type that serializes as `bool`, indicating whether the serializer is
+/// human-readable.
+#[derive(Debug, PartialEq)]
+pub struct Readable;
+
+impl Serialize for Readable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let readable = serializer.is_human_readable();
+        serializer.serialize_bool(readable)
+    }
+}
@@ -467,6 +467,22 @@ mod ser {
                 },
             }
         }
+
+        /// Non-compound values are passed directly to the underlying
+        /// serializer, i.e. are not buffered.
+        #[test]
+        fn serializer_forwards_newtype() {
+            use crate::common::native::{self, Value};
+            use serde::Serialize;
+            use serde_tagged::ser::adj::tuple::Serializer;
+
+            let value = NewtypeStruct(Readable)
+                .serialize(Serializer::new(native::Serializer, "tag"))
+                .unwrap();
+
+            let expect = Value::Seq(vec![Value::Str("tag".to_owned()), Value::Bool(false)]);
+            assert_eq!(value, expect);
+        }
     }
 
     /// Tests for serialization of map-based adjacently-tagged values.
@@ -988,6 +1004,25 @@ mod ser {
                 },
             }
         }
+
+        /// Non-compound values are passed directly to the underlying
+        /// serializer, i.e. are not buffered.
+        #[test]
+        fn serializer_forwards_newtype() {
+            use crate::common::native::{self, Value};
+            use serde::Serialize;
+            use serde_tagged::ser::adj::map::Serializer;
+
+            let value = NewtypeStruct(Readable)
+                .serialize(Serializer::new(native::Serializer, "t", "tag", "c"))
+                .unwrap();
+
+            let expect = Value::Map(vec![
+                (Value::Str("t".to_owned()), Value::Str("tag".to_owned())),
+                (Value::Str("c".to_owned()), Value::Bool(false)),
+            ]);
+            assert_eq!(value, expect);
+        }
     }
 
     /// Tests for serialization of struct-based adjacently-tagged values.
@@ -1881,11 +1916,547 @@ mod ser {
             let _v = serialize("tag", "<tag>", &EnumUntagged::NewtypeP(42)).unwrap();
         }
     }
+
+    /// Tests for serialization in a tagging format selected at runtime.
+    mod dynamic {
+        use crate::common::types::*;
+        use serde_tagged::format::TagFormat;
+        use serde_tagged::ser::dynamic::serialize;
+
+        fn to_json(format: &TagFormat) -> serde_json::Value {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+            serialize(&mut ser, format, "<tag>", &Struct { foo: 42 }).unwrap();
+
+            serde_json::from_slice(&out).unwrap()
+        }
+
+
+        #[test]
+        fn formats() {
+            assert_eq!(
+                to_json(&TagFormat::External),
+                json!({ "<tag>": { "foo": 42 } })
+            );
+            assert_eq!(
+                to_json(&TagFormat::internal("t")),
+                json!({ "t": "<tag>", "foo": 42 })
+            );
+            assert_eq!(
+                to_json(&TagFormat::AdjTuple),
+                json!(["<tag>", { "foo": 42 }])
+            );
+            assert_eq!(
+                to_json(&TagFormat::adj_map("t", "v")),
+                json!({ "t": "<tag>", "v": { "foo": 42 } })
+            );
+            assert_eq!(
+                to_json(&TagFormat::adj_struct("Tagged", "t", "v")),
+                json!({ "t": "<tag>", "v": { "foo": 42 } })
+            );
+        }
+
+        #[test]
+        fn owned_keys() {
+            let internal = json!({ "internal": { "tag_key": "t" } });
+            let internal: TagFormat = serde_json::from_value(internal).unwrap();
+            assert_eq!(to_json(&internal), json!({ "t": "<tag>", "foo": 42 }));
+
+            let struc =
+                json!({ "adj_struct": { "name": "Tagged", "tag_key": "t", "value_key": "v" } });
+            let struc: TagFormat = serde_json::from_value(struc).unwrap();
+
+            let mut ser = serde_json::Serializer::new(Vec::new());
+            let err = serialize(&mut ser, &struc, "<tag>", &Struct { foo: 42 }).unwrap_err();
+            assert!(err.to_string().contains("must be static"));
+        }
+    }
+
+    /// Tests for serialization of interned tags.
+    mod intern {
+        use serde_tagged::ser::intern::Table;
+        use serde_tagged::tagged::{AdjTuple, External};
+
+
+        #[test]
+        fn external() {
+            let table = Table::new();
+
+            let values = vec![
+                External::new(table.tag("circle"), 1),
+                External::new(table.tag("rect"), 2),
+                External::new(table.tag("rect"), 3),
+                External::new(table.tag("circle"), 4),
+            ];
+
+            let value = serde_json::to_value(&values).unwrap();
+            assert_eq!(
+                value,
+                json!([{ "circle": 1 }, { "rect": 2 }, { "1": 3 }, { "0": 4 }])
+            );
+
+            assert_eq!(table.len(), 2);
+            assert_eq!(table.id("rect"), Some(1));
+        }
+
+        #[test]
+        fn adj_tuple() {
+            let table = Table::new();
+
+            let values = vec![
+                AdjTuple::new(table.tag("circle"), 1),
+                AdjTuple::new(table.tag("circle"), 2),
+            ];
+
+            let value = serde_json::to_value(&values).unwrap();
+            assert_eq!(value, json!([["circle", 1], [0, 2]]));
+        }
+
+        #[test]
+        fn numeric_tag() {
+            let table = Table::new();
+
+            let err = serde_json::to_value(External::new(table.tag("42"), 1)).unwrap_err();
+            assert!(err.to_string().contains("consisting only of digits"));
+        }
+    }
+
+    /// Tests for serialization using the native tag syntax.
+    mod native {
+        use crate::common::native::{self, Value};
+        use crate::common::types::*;
+        use serde_tagged::ser::native::{serialize, NAME};
+
+
+        #[test]
+        fn native_format() {
+            struct Tagged;
+
+            impl serde::Serialize for Tagged {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    serialize(ser, NAME, "rect", &Struct { foo: 42 })
+                }
+            }
+
+            let value = native::to_value(&vec![Tagged]).unwrap();
+
+            let expect = Value::Tagged(
+                Box::new(Value::Str("rect".to_owned())),
+                Box::new(Value::Map(vec![(
+                    Value::Str("foo".to_owned()),
+                    Value::I64(42),
+                )])),
+            );
+
+            assert_eq!(value, Value::Seq(vec![expect]));
+        }
+
+        #[test]
+        fn fallback() {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+
+            serialize(&mut ser, NAME, "rect", &Struct { foo: 42 }).unwrap();
+
+            let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(value, json!(["rect", { "foo": 42 }]));
+        }
+    }
+
+    /// Tests for serialization of tags depending on human-readability.
+    mod readable {
+        use crate::common::native::{self, Value};
+        use serde_tagged::format::TagIds;
+        use serde_tagged::ser::readable::Tag;
+
+        const IDS: TagIds = TagIds::new(&[("circle", 1), ("rect", 2)]);
+
+        struct Tagged(&'static str);
+
+        impl serde::Serialize for Tagged {
+            fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                serde_tagged::ser::adj::tuple::serialize(ser, &Tag::new(&IDS, self.0), &42)
+            }
+        }
+
+
+        #[test]
+        fn human_readable() {
+            let value = serde_json::to_value(Tagged("rect")).unwrap();
+            assert_eq!(value, json!(["rect", 42]));
+        }
+
+        #[test]
+        fn compact() {
+            let value = native::to_value(&Tagged("rect")).unwrap();
+            assert_eq!(value, Value::Seq(vec![Value::U64(2), Value::I64(42)]));
+        }
+
+        #[test]
+        fn all_formats() {
+            let tag = Tag::new(&IDS, "circle");
+            let value = native::to_value(&json!({ "r": 1 })).unwrap();
+
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+            serde_tagged::ser::internal::serialize(&mut ser, "type", &tag, &json!({ "r": 1 }))
+                .unwrap();
+
+            let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(json, json!({ "type": "circle", "r": 1 }));
+
+            let ser = native::Serializer;
+            let out = serde_tagged::ser::external::serialize(ser, &tag, &json!({ "r": 1 }));
+            assert_eq!(
+                out.unwrap(),
+                Value::Map(vec![(Value::U64(1), value.clone())])
+            );
+
+            let ser = native::Serializer;
+            let out =
+                serde_tagged::ser::adj::map::serialize(ser, "t", &tag, "v", &json!({ "r": 1 }));
+            assert_eq!(
+                out.unwrap(),
+                Value::Map(vec![
+                    (Value::Str("t".to_owned()), Value::U64(1)),
+                    (Value::Str("v".to_owned()), value),
+                ])
+            );
+        }
+
+        #[test]
+        fn unknown_tag() {
+            let err = serde_json::to_value(Tagged("square")).unwrap_err();
+            assert!(err.to_string().contains("unknown tag `square`"));
+
+            let err = native::to_value(&Tagged("square")).unwrap_err();
+            assert!(err.to_string().contains("unknown tag `square`"));
+        }
+    }
+
+    /// Tests for serialization of values tagged with their type name.
+    mod nominal {
+        use serde_tagged::format::TagFormat;
+        use serde_tagged::ser::nominal::{name, serialize};
+
+        #[derive(Serialize)]
+        #[serde(rename = "point")]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Serialize)]
+        struct Meters(f64);
+
+        #[derive(Serialize)]
+        struct Origin;
+
+        #[derive(Serialize)]
+        enum Shape {
+            Circle { r: i32 },
+            Square(i32),
+        }
+
+        #[derive(Serialize)]
+        struct Flattened {
+            #[serde(flatten)]
+            point: Point,
+        }
+
+        fn to_json<V: serde::Serialize>(format: &TagFormat, value: &V) -> serde_json::Value {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+            serialize(&mut ser, format, value).unwrap();
+
+            serde_json::from_slice(&out).unwrap()
+        }
+
+
+        #[test]
+        fn names() {
+            assert_eq!(name(&Point { x: 1, y: 2 }), Some("point"));
+            assert_eq!(name(&Meters(1.0)), Some("Meters"));
+            assert_eq!(name(&Shape::Circle { r: 1 }), Some("Shape"));
+
+            assert_eq!(name(&42), None);
+            assert_eq!(name(&vec![1, 2]), None);
+        }
+
+        #[test]
+        fn formats() {
+            let point = Point { x: 1, y: 2 };
+
+            assert_eq!(
+                to_json(&TagFormat::External, &point),
+                json!({ "point": { "x": 1, "y": 2 } })
+            );
+            assert_eq!(
+                to_json(&TagFormat::internal("type"), &point),
+                json!({ "type": "point", "x": 1, "y": 2 })
+            );
+            assert_eq!(
+                to_json(&TagFormat::AdjTuple, &point),
+                json!(["point", { "x": 1, "y": 2 }])
+            );
+            assert_eq!(
+                to_json(&TagFormat::adj_map("t", "c"), &point),
+                json!({ "t": "point", "c": { "x": 1, "y": 2 } })
+            );
+            assert_eq!(
+                to_json(&TagFormat::adj_struct("Tagged", "t", "c"), &point),
+                json!({ "t": "point", "c": { "x": 1, "y": 2 } })
+            );
+        }
+
+        #[test]
+        fn unnamed() {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+
+            let err = serialize(&mut ser, &TagFormat::External, &42).unwrap_err();
+            assert!(err.to_string().contains("cannot determine type name"));
+
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+
+            let value = Flattened {
+                point: Point { x: 1, y: 2 },
+            };
+
+            let err = serialize(&mut ser, &TagFormat::External, &value).unwrap_err();
+            assert!(err.to_string().contains("cannot determine type name"));
+        }
+
+        #[test]
+        fn shapes() {
+            let format = TagFormat::AdjTuple;
+
+            assert_eq!(to_json(&format, &Meters(1.5)), json!(["Meters", 1.5]));
+            assert_eq!(to_json(&format, &Origin), json!(["Origin", null]));
+            assert_eq!(
+                to_json(&format, &Shape::Circle { r: 1 }),
+                json!(["Shape", { "Circle": { "r": 1 } }])
+            );
+            assert_eq!(
+                to_json(&format, &Shape::Square(2)),
+                json!(["Shape", { "Square": 2 }])
+            );
+        }
+    }
+
+    /// Tests for serialization as enum variants.
+    mod variant {
+        use crate::common::types::*;
+        use serde_tagged::format::Variants;
+        use serde_tagged::ser::variant::serialize;
+
+        const VARIANTS: Variants = Variants::new("Shape", &["circle", "rect"]);
+
+
+        #[test]
+        fn newtype_variant() {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+
+            serialize(&mut ser, &VARIANTS, "rect", &Struct { foo: 42 }).unwrap();
+
+            let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(value, json!({ "rect": { "foo": 42 } }));
+        }
+
+        #[test]
+        fn unknown_tag() {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+
+            let err = serialize(&mut ser, &VARIANTS, "square", &42).unwrap_err();
+            assert!(err.to_string().contains("unknown variant tag `square`"));
+        }
+    }
+
+    /// Tests for serialization with scoped tag resolvers.
+    #[cfg(feature = "std")]
+    mod scope {
+        use serde::ser::{Error, Serialize, Serializer};
+        use serde_tagged::ser::{with_current_tag_resolver, with_tag_resolver};
+
+        use std::any::TypeId;
+        use std::collections::BTreeMap;
+
+
+        type Resolver = BTreeMap<TypeId, &'static str>;
+
+        /// A value tagged with the tag provided by the scoped resolver.
+        struct Tagged<T>(T);
+
+        impl<T: Serialize + 'static> Serialize for Tagged<T> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                use serde_tagged::ser::external::serialize;
+
+                with_current_tag_resolver(|resolver: &Resolver| {
+                    match resolver.get(&TypeId::of::<T>()) {
+                        Some(tag) => serialize(serializer, tag, &self.0),
+                        None => Err(S::Error::custom("unknown type")),
+                    }
+                })
+                .unwrap_or_else(|| Err(S::Error::custom("no tag resolver in scope")))
+            }
+        }
+
+        fn resolver(int: &'static str, string: &'static str) -> Resolver {
+            let mut resolver = Resolver::new();
+            resolver.insert(TypeId::of::<i32>(), int);
+            resolver.insert(TypeId::of::<String>(), string);
+            resolver
+        }
+
+
+        #[test]
+        fn scoped() {
+            let value = (Tagged(42), Tagged("foo".to_owned()));
+
+            let json = with_tag_resolver(&resolver("int", "str"), || {
+                serde_json::to_value(&value).unwrap()
+            });
+            assert_eq!(json, json!([{ "int": 42 }, { "str": "foo" }]));
+
+            let err = serde_json::to_value(&value).unwrap_err();
+            assert!(err.to_string().contains("no tag resolver in scope"));
+        }
+
+        #[test]
+        fn nested() {
+            let outer = resolver("a", "b");
+            let inner = resolver("c", "d");
+
+            with_tag_resolver(&outer, || {
+                let json = with_tag_resolver(&inner, || serde_json::to_value(Tagged(1)).unwrap());
+                assert_eq!(json, json!({ "c": 1 }));
+
+                let json = serde_json::to_value(Tagged(1)).unwrap();
+                assert_eq!(json, json!({ "a": 1 }));
+            });
+        }
+    }
 }
 
 /// Tests for deserialization of tagged values.
 mod de {
 
+    /// Tests for deserialization with tagging format detection.
+    mod auto {
+        use crate::common::types::*;
+        use serde_tagged::de::auto::deserialize;
+        use serde_tagged::de::WithTag;
+        use serde_tagged::format::TagFormat;
+
+        const FORMATS: [TagFormat; 5] = [
+            TagFormat::adj_struct("Tagged", "t", "c"),
+            TagFormat::adj_map("type", "value"),
+            TagFormat::internal("type"),
+            TagFormat::AdjTuple,
+            TagFormat::External,
+        ];
+
+        fn de(json: &str) -> serde_json::Result<(String, Struct<i32>)> {
+            let mut jde = serde_json::Deserializer::from_str(json);
+            deserialize(&mut jde, &FORMATS, WithTag::new())
+        }
+
+
+        #[test]
+        fn detect() {
+            let expect = ("<tag>".to_owned(), Struct { foo: 42 });
+
+            let docs = [
+                r#"{ "<tag>": { "foo": 42 } }"#,
+                r#"{ "type": "<tag>", "foo": 42 }"#,
+                r#"{ "foo": 42, "type": "<tag>" }"#,
+                r#"[ "<tag>", { "foo": 42 } ]"#,
+                r#"{ "type": "<tag>", "value": { "foo": 42 } }"#,
+                r#"{ "value": { "foo": 42 }, "type": "<tag>" }"#,
+                r#"{ "t": "<tag>", "c": { "foo": 42 } }"#,
+            ];
+
+            for json in &docs {
+                assert_eq!(de(json).unwrap(), expect, "document: {}", json);
+            }
+        }
+
+        #[test]
+        fn roundtrip() {
+            let value = Struct { foo: 42 };
+
+            for format in &FORMATS {
+                let mut out = Vec::new();
+                let mut ser = serde_json::Serializer::new(&mut out);
+                serde_tagged::ser::dynamic::serialize(&mut ser, format, "<tag>", &value).unwrap();
+
+                let json = String::from_utf8(out).unwrap();
+                assert_eq!(de(&json).unwrap(), ("<tag>".to_owned(), Struct { foo: 42 }));
+            }
+        }
+
+        #[test]
+        fn order() {
+            // adjacently tagged maps also contain the tag key of internal tagging
+            let formats = [TagFormat::internal("type")];
+            let json = r#"{ "type": "<tag>", "value": 42 }"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let result: Result<(String, Struct<i32>), _> =
+                deserialize(&mut jde, &formats, WithTag::new());
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("missing field `foo`"));
+        }
+
+        #[test]
+        fn undetectable() {
+            let err = de("42").unwrap_err().to_string();
+
+            assert!(err.contains("cannot detect tagging format"));
+            assert!(
+                err.contains("external: expected a map with a single entry, found integer `42`")
+            );
+            assert!(err.contains("internal: expected a map, found integer `42`"));
+            assert!(err.contains("adj::tuple: expected a sequence with two elements"));
+            assert!(err.contains("adj::map: expected a map with keys `type` and `value`"));
+            assert!(err.contains("adj::struc: expected a map with keys `t` and `c`"));
+
+            let err = de(r#"{ "a": 1, "b": 2 }"#).unwrap_err().to_string();
+
+            assert!(err.contains(
+                "external: expected a map with a single entry, found a map with 2 entries"
+            ));
+            assert!(err.contains("internal: map does not contain tag key `type`"));
+            assert!(err.contains(
+                "adj::tuple: expected a sequence with two elements, found a map with 2 entries"
+            ));
+            assert!(err.contains("adj::map: map does not contain keys `type` and `value`"));
+
+            let err = de("[1, 2, 3]").unwrap_err().to_string();
+            assert!(err.contains("found a sequence with 3 elements"));
+        }
+
+        #[test]
+        fn no_formats() {
+            let mut jde = serde_json::Deserializer::from_str("[1, 2]");
+            let result: Result<(String, i32), _> = deserialize(&mut jde, &[], WithTag::new());
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("no candidate formats"));
+        }
+    }
+
     /// Tests for deserialization of externally-tagged values.
     mod external {
         use crate::common::types::*;
@@ -3236,4 +3807,2880 @@ mod de {
             );
         }
     }
+
+    /// Tests for normalization of string-based tags.
+    mod normalize {
+        use serde_tagged::de::normalize::{Case, Normalize, Normalized};
+        use serde_tagged::util::TagString;
+
+
+        #[test]
+        fn identity() {
+            let tag = Normalize::new().apply(" com.example.a ".into());
+            assert_eq!(&*tag, " com.example.a ");
+        }
+
+        #[test]
+        fn trim() {
+            let policy = Normalize::new().trim();
+
+            assert_eq!(&*policy.apply(" a\t".into()), "a");
+            assert_eq!(&*policy.apply(" a\t".to_owned().into()), "a");
+            assert_eq!(&*policy.apply("   ".into()), "");
+        }
+
+        #[test]
+        fn strip_namespace() {
+            let policy = Normalize::new().strip_namespace('.');
+
+            assert_eq!(&*policy.apply("com.example.A".into()), "A");
+            assert_eq!(&*policy.apply("com.example.A".to_owned().into()), "A");
+            assert_eq!(&*policy.apply("A".into()), "A");
+        }
+
+        #[test]
+        fn case() {
+            let lower = Normalize::new().case(Case::Lower);
+            let upper = Normalize::new().case(Case::Upper);
+
+            assert_eq!(&*lower.apply("Abc".into()), "abc");
+            assert_eq!(&*upper.apply("Abc".into()), "ABC");
+        }
+
+        #[test]
+        fn case_titlecase() {
+            let lower = Normalize::new().case(Case::Lower);
+            let upper = Normalize::new().case(Case::Upper);
+
+            // U+01C5 is a titlecase letter, i.e. neither upper- nor lowercase
+            assert_eq!(&*lower.apply("\u{1c5}a".into()), "\u{1c6}a");
+            assert_eq!(&*upper.apply("\u{1c5}A".into()), "\u{1c4}A");
+
+            match lower.apply("abc".into()) {
+                TagString::Borrowed(tag) => assert_eq!(tag, "abc"),
+                _ => panic!("normalized tag has been allocated"),
+            }
+        }
+
+        #[test]
+        fn combined() {
+            let policy = Normalize::new()
+                .trim()
+                .strip_namespace('.')
+                .case(Case::Upper);
+
+            assert_eq!(&*policy.apply(" com.example.a ".into()), "A");
+            assert_eq!(&*policy.apply(" com.example.a ".to_owned().into()), "A");
+        }
+
+        #[test]
+        fn no_allocation() {
+            let policy = Normalize::new()
+                .trim()
+                .strip_namespace('.')
+                .case(Case::Upper);
+
+            match policy.apply(" com.example.A ".into()) {
+                TagString::Borrowed(tag) => assert_eq!(tag, "A"),
+                TagString::Owned(_) => panic!("normalized tag has been allocated"),
+            }
+        }
+
+        #[test]
+        fn lookup() {
+            use serde_tagged::de::external::deserialize as de;
+            use std::collections::BTreeMap;
+            use std::marker::PhantomData;
+
+            let json = r#"{ " com.example.a ": 42 }"#;
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert("A", PhantomData::<i32>);
+
+            let policy = Normalize::new()
+                .trim()
+                .strip_namespace('.')
+                .case(Case::Upper);
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, Normalized::new(seeds, policy)).unwrap();
+
+            assert_eq!(v, 42);
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn lookup_ref() {
+            use serde::Deserialize;
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::BoxFnSeed;
+            use std::collections::BTreeMap;
+
+            fn deserialize_i32<'de>(
+                de: &mut dyn erased_serde::Deserializer<'de>,
+            ) -> Result<i32, erased_serde::Error> {
+                i32::deserialize(de)
+            }
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert("a", BoxFnSeed::new(deserialize_i32));
+
+            let registry = Normalized::new(seeds, Normalize::new().trim().case(Case::Lower));
+
+            for json in &[r#"{ "a": 42 }"#, r#"{ "A": 42 }"#, r#"{ " A ": 42 }"#] {
+                let mut jde = serde_json::Deserializer::from_str(json);
+                let v = de(&mut jde, &registry).unwrap();
+
+                assert_eq!(v, 42);
+            }
+        }
+    }
+
+    /// Tests for the dynamic seeds provided in `de`.
+    mod seed {
+        use serde::de::{Deserialize, DeserializeSeed};
+        use serde_tagged::de::registry::FnRegistry;
+        use serde_tagged::de::FnPtrSeed;
+        #[cfg(feature = "erased")]
+        use serde_tagged::de::{ArcFnSeed, LocalFnSeed, SendFnSeed};
+
+        #[cfg(feature = "erased")]
+        use std::collections::BTreeMap;
+
+
+        fn de<'de, S: DeserializeSeed<'de>>(seed: S, json: &'de str) -> S::Value {
+            seed.deserialize(&mut serde_json::Deserializer::from_str(json))
+                .unwrap()
+        }
+
+
+        trait Shape {
+            fn area(&self) -> f64;
+        }
+
+        #[derive(Deserialize)]
+        struct Square(f64);
+
+        impl Shape for Square {
+            fn area(&self) -> f64 {
+                self.0 * self.0
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct Rect {
+            w: f64,
+            h: f64,
+        }
+
+        impl Shape for Rect {
+            fn area(&self) -> f64 {
+                self.w * self.h
+            }
+        }
+
+        fn de_square(de: serde_json::Value) -> Result<Box<dyn Shape>, serde_json::Error> {
+            Ok(Box::new(Square::deserialize(de)?))
+        }
+
+        fn de_rect(de: serde_json::Value) -> Result<Box<dyn Shape>, serde_json::Error> {
+            Ok(Box::new(Rect::deserialize(de)?))
+        }
+
+        fn fn_registry() -> FnRegistry<serde_json::Value, Box<dyn Shape>> {
+            let mut registry = FnRegistry::new();
+            registry.insert("square", FnPtrSeed::new(de_square));
+            registry.insert("rect", FnPtrSeed::new(de_rect));
+            registry
+        }
+
+
+        #[test]
+        fn fn_ptr() {
+            use serde_tagged::de::external::deserialize;
+
+            let registry = fn_registry();
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "square": 2.0 }"#);
+            assert_eq!(deserialize(&mut jde, &registry).unwrap().area(), 4.0);
+
+            let seed = registry["rect"];
+            let value = de(seed, r#"{ "w": 2.0, "h": 3.0 }"#);
+            assert_eq!(value.area(), 6.0);
+        }
+
+        #[test]
+        fn fn_ptr_formats() {
+            use serde_tagged::de::{adj, internal};
+
+            let registry = fn_registry();
+
+            let json = r#"["rect", { "w": 1.0, "h": 3.0 }]"#;
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let value = adj::tuple::deserialize(&mut jde, &registry).unwrap();
+            assert_eq!(value.area(), 3.0);
+
+            let json = r#"{ "t": "rect", "w": 2.0, "h": 2.5 }"#;
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let value = internal::deserialize(&mut jde, "t", &registry).unwrap();
+            assert_eq!(value.area(), 5.0);
+        }
+
+        #[test]
+        fn fn_ptr_error() {
+            use serde_tagged::de::external::deserialize;
+
+            let registry = fn_registry();
+
+            let json = r#"{ "rect": { "w": 2.0 } }"#;
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let err = deserialize(&mut jde, &registry).err().unwrap();
+            assert!(err.to_string().contains("missing field `h`"));
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn arc() {
+            use serde_tagged::de::external::deserialize;
+
+            let mut registry = BTreeMap::new();
+            registry.insert("i", ArcFnSeed::new(|de| i64::deserialize(de)));
+
+            let clone = registry.clone();
+            assert_eq!(de(&registry["i"], "42"), 42);
+            assert_eq!(de(clone["i"].clone(), "42"), 42);
+
+            let handle = std::thread::spawn(move || {
+                let mut jde = serde_json::Deserializer::from_str(r#"{ "i": 7 }"#);
+                deserialize(&mut jde, &clone).unwrap()
+            });
+            assert_eq!(handle.join().unwrap(), 7);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "i": 8 }"#);
+            assert_eq!(deserialize(&mut jde, registry).unwrap(), 8);
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn send() {
+            use serde_tagged::de::adj::tuple::deserialize;
+
+            let (tx, rx) = std::sync::mpsc::channel();
+
+            let mut registry = BTreeMap::new();
+            registry.insert(
+                "i",
+                SendFnSeed::new(move |de| {
+                    let value = i64::deserialize(de)?;
+                    tx.send(value).unwrap();
+                    Ok(value)
+                }),
+            );
+
+            let handle = std::thread::spawn(move || {
+                let mut jde = serde_json::Deserializer::from_str(r#"["i", 3]"#);
+                let value = deserialize(&mut jde, &registry).unwrap();
+                (value, de(registry.remove("i").unwrap(), "4"))
+            });
+
+            assert_eq!(handle.join().unwrap(), (3, 4));
+            assert_eq!(rx.iter().collect::<Vec<_>>(), vec![3, 4]);
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn local() {
+            use serde_tagged::de::external::deserialize;
+            use std::cell::Cell;
+            use std::rc::Rc;
+
+            let count = Rc::new(Cell::new(0));
+            let captured = count.clone();
+
+            let mut registry = BTreeMap::new();
+            registry.insert(
+                "i",
+                LocalFnSeed::new(move |de| {
+                    captured.set(captured.get() + 1);
+                    i64::deserialize(de)
+                }),
+            );
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "i": 1 }"#);
+            assert_eq!(deserialize(&mut jde, &registry).unwrap(), 1);
+            assert_eq!(de(registry.remove("i").unwrap(), "2"), 2);
+
+            assert_eq!(count.get(), 2);
+        }
+    }
+
+    /// Tests for the registries provided in `de::registry`.
+    mod registry {
+        use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+        #[cfg(feature = "erased")]
+        use serde_tagged::de::registry::ContextRegistry;
+        #[cfg(feature = "erased")]
+        use serde_tagged::de::BoxFnSeedWithContext;
+
+
+        /// A seed deserializing an integer, usable by reference.
+        struct Int;
+
+        impl<'de> DeserializeSeed<'de> for &Int {
+            type Value = i64;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                i64::deserialize(deserializer)
+            }
+        }
+
+
+        #[test]
+        fn registry() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::{Entry, Registry};
+            use serde_tagged::format::TagFormat;
+
+            let mut registry = Registry::new();
+            registry.insert("B", Entry::new::<i64>(TagFormat::External, Int));
+            registry.insert("A", Entry::new::<i64>(TagFormat::AdjTuple, Int));
+
+            assert_eq!(registry.len(), 2);
+            assert_eq!(registry.tags().collect::<Vec<_>>(), vec!["A", "B"]);
+            assert_eq!(registry.get("A").unwrap().type_name(), "i64");
+            assert_eq!(*registry.get("A").unwrap().format(), TagFormat::AdjTuple);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "B": 42 }"#);
+            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "C": 42 }"#);
+            let err = de(&mut jde, &registry).unwrap_err();
+            assert!(err.to_string().contains("unknown tag `C`"));
+        }
+
+        #[test]
+        fn registry_types() {
+            use serde_tagged::de::registry::{Entry, Registry};
+            use serde_tagged::format::TagFormat;
+
+            let mut registry = Registry::new();
+            registry.insert("A", Entry::new::<i64>(TagFormat::internal("t"), Int));
+            registry.insert(
+                "B",
+                Entry::new::<i64>(TagFormat::adj_struct("Tagged", "t", "c"), Int),
+            );
+
+            let types: Vec<_> = registry.types().collect();
+
+            assert_eq!(
+                serde_json::to_value(&types).unwrap(),
+                json!([
+                    {
+                        "tag": "A",
+                        "type": "i64",
+                        "format": { "internal": { "tag_key": "t" } },
+                    },
+                    {
+                        "tag": "B",
+                        "type": "i64",
+                        "format": {
+                            "adj_struct": { "name": "Tagged", "tag_key": "t", "value_key": "c" },
+                        },
+                    },
+                ])
+            );
+        }
+
+        #[test]
+        fn registry_merge() {
+            use serde_tagged::de::registry::{Conflict, Entry, Merge, Registry};
+            use serde_tagged::format::TagFormat;
+
+            let mut a = Registry::new();
+            a.insert("A", Entry::new::<i64>(TagFormat::External, Int));
+
+            let mut b = Registry::new();
+            b.insert("A", Entry::new::<u8>(TagFormat::External, Int));
+            b.insert("B", Entry::new::<u8>(TagFormat::External, Int));
+
+            let report = a.merge_from(b, Conflict::KeepFirst).unwrap();
+
+            assert_eq!(report.duplicates(), &["A".to_owned()]);
+            assert_eq!(a.get("A").unwrap().type_name(), "i64");
+            assert_eq!(a.get("B").unwrap().type_name(), "u8");
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn shared() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::SharedRegistry;
+
+            let registry = SharedRegistry::new();
+            assert!(registry.is_empty());
+
+            registry.register("A", Int);
+            assert!(registry.contains("A"));
+            assert_eq!(registry.len(), 1);
+            assert_eq!(registry.tags(), vec!["A".to_owned()]);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "A": 42 }"#);
+            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+
+            assert!(registry.unregister("A").is_some());
+            assert!(registry.unregister("A").is_none());
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "A": 42 }"#);
+            let err = de(&mut jde, &registry).unwrap_err();
+            assert!(err.to_string().contains("unknown tag `A`"));
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn shared_unregister_in_flight() {
+            use serde_tagged::de::registry::SharedRegistry;
+            use serde_tagged::de::SeedFactory;
+
+            let registry = SharedRegistry::new();
+            registry.register("A", Int);
+
+            let seed = (&registry).seed::<serde_json::Error>("A".into()).unwrap();
+            registry.unregister("A");
+
+            let mut jde = serde_json::Deserializer::from_str("42");
+            assert_eq!(seed.deserialize(&mut jde).unwrap(), 42);
+
+            assert!((&registry).seed::<serde_json::Error>("A".into()).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn shared_concurrent() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::SharedRegistry;
+
+            let registry = SharedRegistry::new();
+            registry.register("A", Int);
+
+            std::thread::scope(|scope| {
+                for _ in 0..4 {
+                    scope.spawn(|| {
+                        for _ in 0..100 {
+                            let mut jde = serde_json::Deserializer::from_str(r#"{ "A": 42 }"#);
+                            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+
+                            let mut jde = serde_json::Deserializer::from_str(r#"{ "B": 42 }"#);
+                            if let Err(err) = de(&mut jde, &registry) {
+                                assert!(err.to_string().contains("unknown tag `B`"));
+                            }
+                        }
+                    });
+                }
+
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        registry.register("B", Int);
+                        registry.unregister("B");
+                    }
+                });
+            });
+
+            assert!(registry.contains("A"));
+            assert!(!registry.contains("B"));
+        }
+
+        #[test]
+        fn namespaced() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::Namespaced;
+
+            let mut registry = Namespaced::new('.');
+            registry.insert("vendor", map! { "Type" => Int });
+            registry.insert("other", map! { "Type" => Int, "Other" => Int });
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "vendor.Type": 42 }"#);
+            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "other.Other": 42 }"#);
+            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "vendor.Other": 42 }"#);
+            assert!(de(&mut jde, &registry).is_err());
+        }
+
+        #[test]
+        fn namespaced_nested() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::Namespaced;
+            use std::marker::PhantomData;
+
+            let json = r#"{ "vendor.module.Type": 42 }"#;
+
+            let mut module = Namespaced::new('.');
+            module.insert("module", map! { "Type" => PhantomData::<i64> });
+
+            let mut registry = Namespaced::new('.');
+            registry.insert("vendor", module);
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            assert_eq!(de(&mut jde, registry).unwrap(), 42);
+        }
+
+        #[test]
+        fn namespaced_error_unknown() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::Namespaced;
+
+            let mut registry = Namespaced::new('.');
+            registry.insert("vendor", map! { "Type" => Int });
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "other.Type": 42 }"#);
+            let err = de(&mut jde, &registry).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("unknown tag `other.Type` (unknown namespace `other`)"));
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "Type": 42 }"#);
+            let err = de(&mut jde, &registry).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("unknown tag `Type` (no namespace specified)"));
+        }
+
+        #[test]
+        fn merge_error() {
+            use serde_tagged::de::registry::{Conflict, Merge};
+
+            let mut registry = map! { "A" => 1, "B" => 2 };
+
+            let err = registry
+                .merge_from(
+                    vec![("C", 3), ("B", 4), ("D", 5), ("D", 6)],
+                    Conflict::Error,
+                )
+                .unwrap_err();
+
+            assert_eq!(err.duplicates(), &["B".to_owned(), "D".to_owned()]);
+            assert_eq!(err.to_string(), "duplicate tags in registry: `B`, `D`");
+            assert_eq!(registry, map! { "A" => 1, "B" => 2 });
+
+            let report = registry
+                .merge_from(map! { "C" => 3, "D" => 4 }, Conflict::Error)
+                .unwrap();
+
+            assert!(report.is_clean());
+            assert_eq!(registry, map! { "A" => 1, "B" => 2, "C" => 3, "D" => 4 });
+        }
+
+        #[test]
+        fn merge_keep_first() {
+            use serde_tagged::de::registry::{Conflict, Merge};
+
+            let mut registry = map! { "A" => 1, "B" => 2 };
+
+            let report = registry
+                .merge_from(vec![("B", 3), ("C", 4), ("C", 5)], Conflict::KeepFirst)
+                .unwrap();
+
+            assert_eq!(report.duplicates(), &["B".to_owned(), "C".to_owned()]);
+            assert_eq!(registry, map! { "A" => 1, "B" => 2, "C" => 4 });
+        }
+
+        #[test]
+        fn merge_keep_last() {
+            use serde_tagged::de::registry::{Conflict, Merge};
+
+            let mut registry = map! { "A" => 1, "B" => 2 };
+
+            let report = registry
+                .merge_from(vec![("B", 3), ("C", 4), ("C", 5)], Conflict::KeepLast)
+                .unwrap();
+
+            assert_eq!(report.duplicates(), &["B".to_owned(), "C".to_owned()]);
+            assert_eq!(registry, map! { "A" => 1, "B" => 3, "C" => 5 });
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn merge_hash_map() {
+            use serde_tagged::de::registry::{Conflict, Merge};
+            use std::collections::HashMap;
+
+            let mut registry: HashMap<String, i32> = HashMap::new();
+            registry.insert("A".to_owned(), 1);
+
+            let report = registry
+                .merge_from(vec![("A".to_owned(), 2)], Conflict::KeepLast)
+                .unwrap();
+
+            assert_eq!(report.duplicates(), &["A".to_owned()]);
+            assert_eq!(registry["A"], 2);
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn merge_shared() {
+            use serde_tagged::de::external::deserialize as de;
+            use serde_tagged::de::registry::{Conflict, Merge, SharedRegistry};
+
+            let mut registry = SharedRegistry::new();
+            registry.register("A", Int);
+
+            let err = registry
+                .merge_from(vec![("A", Int), ("B", Int)], Conflict::Error)
+                .unwrap_err();
+
+            assert_eq!(err.duplicates(), &["A".to_owned()]);
+            assert!(!registry.contains("B"));
+
+            let report = registry
+                .merge_from(vec![("B", Int)], Conflict::Error)
+                .unwrap();
+            assert!(report.is_clean());
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "B": 42 }"#);
+            assert_eq!(de(&mut jde, &registry).unwrap(), 42);
+        }
+
+        #[cfg(feature = "erased")]
+        fn context_registry() -> ContextRegistry<Vec<String>, String> {
+            let mut registry = ContextRegistry::new();
+            registry.insert(
+                "name",
+                BoxFnSeedWithContext::new(|interned: &mut Vec<String>, de| {
+                    let name = String::deserialize(de)?;
+                    if !interned.contains(&name) {
+                        interned.push(name.clone());
+                    }
+                    Ok(name)
+                }),
+            );
+            registry
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn context() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let registry = context_registry();
+            let mut interned = Vec::new();
+
+            for json in &[
+                r#"{ "name": "a" }"#,
+                r#"{ "name": "b" }"#,
+                r#"{ "name": "a" }"#,
+            ] {
+                let mut jde = serde_json::Deserializer::from_str(json);
+                de(&mut jde, registry.with_context(&mut interned)).unwrap();
+            }
+
+            assert_eq!(interned, vec!["a".to_owned(), "b".to_owned()]);
+            assert_eq!(registry.tags().collect::<Vec<_>>(), vec!["name"]);
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn context_seed() {
+            use serde_tagged::de::adj::tuple::deserialize as de;
+            use serde_tagged::de::SeedFactory;
+            use serde_tagged::util::TagString;
+
+            let registry = context_registry();
+            let mut interned = vec!["a".to_owned()];
+
+            let seed = registry
+                .with_context(&mut interned)
+                .seed::<serde_json::Error>(TagString::from("name"))
+                .unwrap();
+
+            let value = seed.deserialize(&mut serde_json::Deserializer::from_str(r#""b""#));
+            assert_eq!(value.unwrap(), "b");
+
+            let mut jde = serde_json::Deserializer::from_str(r#"["name", "c"]"#);
+            assert_eq!(
+                de(&mut jde, registry.with_context(&mut interned)).unwrap(),
+                "c"
+            );
+
+            assert_eq!(interned.len(), 3);
+        }
+
+        #[test]
+        #[cfg(feature = "erased")]
+        fn context_error_unknown() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let registry = context_registry();
+            let mut interned = Vec::new();
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "other": "a" }"#);
+            let err = de(&mut jde, registry.with_context(&mut interned)).unwrap_err();
+
+            assert!(err.to_string().contains("unknown tag `other`"));
+            assert!(interned.is_empty());
+        }
+    }
+
+    /// Tests for deserialization with scoped registries.
+    #[cfg(all(feature = "std", feature = "erased"))]
+    mod scope {
+        use serde::de::{Deserialize, Deserializer, Error};
+        use serde_tagged::de::{with_current_registry, with_registry, BoxFnSeed};
+
+        use std::collections::BTreeMap;
+
+
+        type Registry = BTreeMap<&'static str, BoxFnSeed<Box<dyn Named>>>;
+
+        trait Named {
+            fn name(&self) -> String;
+        }
+
+        #[derive(Deserialize)]
+        struct A(i32);
+
+        impl Named for A {
+            fn name(&self) -> String {
+                format!("A({})", self.0)
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct B(i32);
+
+        impl Named for B {
+            fn name(&self) -> String {
+                format!("B({})", self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Box<dyn Named> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                use serde_tagged::de::external::deserialize;
+
+                with_current_registry(|registry: &Registry| deserialize(deserializer, registry))
+                    .unwrap_or_else(|| Err(D::Error::custom("no registry in scope")))
+            }
+        }
+
+        fn registry_ab() -> Registry {
+            let mut registry = Registry::new();
+            registry.insert(
+                "a",
+                BoxFnSeed::new(|de| Ok(Box::new(A::deserialize(de)?) as Box<dyn Named>)),
+            );
+            registry.insert(
+                "b",
+                BoxFnSeed::new(|de| Ok(Box::new(B::deserialize(de)?) as Box<dyn Named>)),
+            );
+            registry
+        }
+
+        fn registry_ba() -> Registry {
+            let mut registry = Registry::new();
+            registry.insert(
+                "a",
+                BoxFnSeed::new(|de| Ok(Box::new(B::deserialize(de)?) as Box<dyn Named>)),
+            );
+            registry.insert(
+                "b",
+                BoxFnSeed::new(|de| Ok(Box::new(A::deserialize(de)?) as Box<dyn Named>)),
+            );
+            registry
+        }
+
+        fn names(json: &str) -> Vec<String> {
+            let values: Vec<Box<dyn Named>> = serde_json::from_str(json).unwrap();
+            values.iter().map(|v| v.name()).collect()
+        }
+
+
+        #[test]
+        fn scoped() {
+            let json = r#"[{ "a": 1 }, { "b": 2 }]"#;
+
+            let v = with_registry(&registry_ab(), || names(json));
+            assert_eq!(v, vec!["A(1)", "B(2)"]);
+
+            let v = with_registry(&registry_ba(), || names(json));
+            assert_eq!(v, vec!["B(1)", "A(2)"]);
+        }
+
+        #[test]
+        fn nested() {
+            let outer = registry_ab();
+            let inner = registry_ba();
+
+            with_registry(&outer, || {
+                assert_eq!(
+                    with_registry(&inner, || names(r#"[{ "a": 1 }]"#)),
+                    vec!["B(1)"]
+                );
+                assert_eq!(names(r#"[{ "a": 1 }]"#), vec!["A(1)"]);
+            });
+        }
+
+        #[test]
+        fn parallel() {
+            let json = r#"[{ "a": 1 }, { "b": 2 }]"#;
+
+            std::thread::scope(|s| {
+                let ab = s.spawn(|| {
+                    let registry = registry_ab();
+                    (0..100)
+                        .map(|_| with_registry(&registry, || names(json)))
+                        .all(|v| v == ["A(1)", "B(2)"])
+                });
+
+                let ba = s.spawn(|| {
+                    let registry = registry_ba();
+                    (0..100)
+                        .map(|_| with_registry(&registry, || names(json)))
+                        .all(|v| v == ["B(1)", "A(2)"])
+                });
+
+                assert!(ab.join().unwrap());
+                assert!(ba.join().unwrap());
+            });
+        }
+
+        #[test]
+        fn unwind() {
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+
+            let registry = registry_ab();
+            let result = catch_unwind(AssertUnwindSafe(|| with_registry(&registry, || panic!())));
+
+            assert!(result.is_err());
+            assert!(with_current_registry(|_: &Registry| ()).is_none());
+        }
+
+        #[test]
+        fn error_no_registry() {
+            let result: Result<Box<dyn Named>, _> = serde_json::from_str(r#"{ "a": 1 }"#);
+            assert!(result.is_err());
+
+            let err = result.err().unwrap();
+            assert!(err.to_string().contains("no registry in scope"));
+        }
+    }
+
+    /// Tests for deserialization in a tagging format selected at runtime.
+    mod dynamic {
+        use crate::common::types::*;
+        use serde_tagged::de::dynamic::{deserialize, deserialize_seed};
+        use serde_tagged::de::WithTag;
+        use serde_tagged::format::TagFormat;
+
+        const FORMATS: [TagFormat; 5] = [
+            TagFormat::External,
+            TagFormat::internal("t"),
+            TagFormat::AdjTuple,
+            TagFormat::adj_map("t", "v"),
+            TagFormat::adj_struct("Tagged", "t", "v"),
+        ];
+
+
+        #[test]
+        fn roundtrip() {
+            let value = Struct { foo: 42 };
+
+            for format in &FORMATS {
+                let mut out = Vec::new();
+                let mut ser = serde_json::Serializer::new(&mut out);
+                serde_tagged::ser::dynamic::serialize(&mut ser, format, "<tag>", &value).unwrap();
+
+                let mut jde = serde_json::Deserializer::from_slice(&out);
+                let (tag, de): (String, Struct<i32>) =
+                    deserialize(&mut jde, format, WithTag::new()).unwrap();
+
+                assert_eq!(tag, "<tag>");
+                assert_eq!(de, value);
+            }
+        }
+
+        #[test]
+        fn with_seed() {
+            use serde_tagged::de::readable::TagSeed;
+            use serde_tagged::format::TagIds;
+            use serde_tagged::util::TagString;
+
+            const IDS: TagIds = TagIds::new(&[("rect", 7)]);
+
+            let json = r#"{ "t": "rect", "v": 42 }"#;
+            let format = &FORMATS[3];
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let (tag, value): (TagString, i32) =
+                deserialize_seed(&mut jde, format, WithTag::new(), TagSeed::new(&IDS)).unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn owned_keys() {
+            let value = Struct { foo: 42 };
+
+            for format in &FORMATS[..4] {
+                let json = serde_json::to_value(format).unwrap();
+                let format: TagFormat = serde_json::from_value(json).unwrap();
+
+                let mut out = Vec::new();
+                let mut ser = serde_json::Serializer::new(&mut out);
+                serde_tagged::ser::dynamic::serialize(&mut ser, &format, "<tag>", &value).unwrap();
+
+                let mut jde = serde_json::Deserializer::from_slice(&out);
+                let (tag, de): (String, Struct<i32>) =
+                    deserialize(&mut jde, &format, WithTag::new()).unwrap();
+
+                assert_eq!(tag, "<tag>");
+                assert_eq!(de, value);
+            }
+        }
+
+        #[test]
+        fn owned_adj_struct() {
+            let json = serde_json::to_value(&FORMATS[4]).unwrap();
+            let format: TagFormat = serde_json::from_value(json).unwrap();
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "t": "<tag>", "v": 42 }"#);
+            let result: Result<(String, i32), _> = deserialize(&mut jde, &format, WithTag::new());
+
+            assert!(result.unwrap_err().to_string().contains("must be static"));
+        }
+
+        #[test]
+        fn owned_missing_tag() {
+            let format: TagFormat =
+                serde_json::from_value(json!({ "internal": { "tag_key": "t" } })).unwrap();
+
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "foo": 42 }"#);
+            let result: Result<(String, Struct<i32>), _> =
+                deserialize(&mut jde, &format, WithTag::new());
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("missing field `t`"));
+        }
+
+        #[test]
+        fn mismatch() {
+            let json = r#"{ "t": "<tag>", "v": 42 }"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let result: Result<(String, i32), _> =
+                deserialize(&mut jde, &TagFormat::AdjTuple, WithTag::new());
+
+            assert!(result.is_err());
+        }
+    }
+
+    /// Tests for deserialization of interned tags.
+    mod intern {
+        use serde_tagged::de::intern::{Interned, Table, Tag};
+        use serde_tagged::de::WithTag;
+        use serde_tagged::util::TagString;
+
+        use std::fmt;
+
+        /// A sequence of tagged values sharing a table.
+        struct Seq<'t> {
+            table:    &'t Table,
+            external: bool,
+        }
+
+        impl<'t, 'de> serde::de::DeserializeSeed<'de> for Seq<'t> {
+            type Value = Vec<(String, i32)>;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        impl<'t, 'de> serde::de::Visitor<'de> for Seq<'t> {
+            type Value = Vec<(String, i32)>;
+
+            fn expecting(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+                write!(fmtr, "a sequence of tagged values")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut values = Vec::new();
+
+                while let Some(value) = seq.next_element_seed(Element(self.table, self.external))? {
+                    values.push(value);
+                }
+
+                Ok(values)
+            }
+        }
+
+        struct Element<'t>(&'t Table, bool);
+
+        impl<'t, 'de> serde::de::DeserializeSeed<'de> for Element<'t> {
+            type Value = (String, i32);
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let factory = Interned::new(self.0, WithTag::new());
+
+                let (tag, value): (TagString, i32) = if self.1 {
+                    serde_tagged::de::external::deserialize(deserializer, factory)?
+                } else {
+                    serde_tagged::de::adj::tuple::deserialize(deserializer, factory)?
+                };
+
+                Ok((tag.to_string(), value))
+            }
+        }
+
+        fn deserialize(json: &str, external: bool) -> serde_json::Result<Vec<(String, i32)>> {
+            use serde::de::DeserializeSeed;
+
+            let table = Table::new();
+            let mut jde = serde_json::Deserializer::from_str(json);
+
+            Seq {
+                table: &table,
+                external,
+            }
+            .deserialize(&mut jde)
+        }
+
+        fn expect() -> Vec<(String, i32)> {
+            vec![
+                ("circle".to_owned(), 1),
+                ("rect".to_owned(), 2),
+                ("rect".to_owned(), 3),
+                ("circle".to_owned(), 4),
+            ]
+        }
+
+
+        #[test]
+        fn tags() {
+            let value: Tag = serde_json::from_str(r#""circle""#).unwrap();
+            assert_eq!(value, Tag::Tag("circle".into()));
+
+            let value: Tag = serde_json::from_str("3").unwrap();
+            assert_eq!(value, Tag::Id(3));
+
+            let value: Tag = serde_json::from_str(r#""3""#).unwrap();
+            assert_eq!(value, Tag::Id(3));
+        }
+
+        #[test]
+        fn external() {
+            let json = r#"[{ "circle": 1 }, { "rect": 2 }, { "1": 3 }, { "0": 4 }]"#;
+            assert_eq!(deserialize(json, true).unwrap(), expect());
+        }
+
+        #[test]
+        fn adj_tuple() {
+            let json = r#"[["circle", 1], ["rect", 2], [1, 3], [0, 4]]"#;
+            assert_eq!(deserialize(json, false).unwrap(), expect());
+        }
+
+        #[test]
+        fn repeated_tag() {
+            let json = r#"[["circle", 1], ["circle", 2], ["rect", 3], [1, 4]]"#;
+
+            let expect = vec![
+                ("circle".to_owned(), 1),
+                ("circle".to_owned(), 2),
+                ("rect".to_owned(), 3),
+                ("rect".to_owned(), 4),
+            ];
+
+            assert_eq!(deserialize(json, false).unwrap(), expect);
+        }
+
+        #[test]
+        fn resolved_tags() {
+            let table = Table::new();
+            let mut jde = serde_json::Deserializer::from_str(r#"["circle", 1] [0, 2]"#);
+
+            let factory = Interned::new(&table, WithTag::new());
+            let (tag, _): (TagString, i32) =
+                serde_tagged::de::adj::tuple::deserialize(&mut jde, factory).unwrap();
+            assert_eq!(tag, TagString::Borrowed("circle"));
+
+            let factory = Interned::new(&table, WithTag::new());
+            let (tag, _): (TagString, i32) =
+                serde_tagged::de::adj::tuple::deserialize(&mut jde, factory).unwrap();
+            assert_eq!(tag, TagString::Owned("circle".to_owned()));
+
+            // resolved tags can be moved across threads
+            fn assert_send<T: Send + Sync>(_: &T) {}
+            assert_send(&tag);
+
+            assert_eq!(table.id("circle"), Some(0));
+            assert_eq!(table.len(), 1);
+        }
+
+        #[test]
+        fn unknown_id() {
+            let json = r#"[["circle", 1], [1, 2]]"#;
+
+            let err = deserialize(json, false).unwrap_err();
+            assert!(err.to_string().contains("unknown tag id `1`"));
+        }
+
+        #[test]
+        fn roundtrip() {
+            use serde_tagged::tagged::External;
+
+            let table = serde_tagged::ser::intern::Table::new();
+
+            let expect = expect();
+            let values: Vec<_> = expect
+                .iter()
+                .map(|(tag, value)| External::new(table.tag(tag), *value))
+                .collect();
+
+            let json = serde_json::to_string(&values).unwrap();
+            assert_eq!(deserialize(&json, true).unwrap(), expect);
+        }
+    }
+
+    /// Tests for deserialization using the native tag syntax.
+    mod native {
+        use crate::common::native::{self, Value};
+        use crate::common::types::*;
+        use serde_tagged::de::native::deserialize;
+        use serde_tagged::de::{WithTag, WithoutTag};
+        use serde_tagged::ser::native::NAME;
+
+
+        #[test]
+        fn native_format() {
+            let value = Value::Tagged(
+                Box::new(Value::Str("rect".to_owned())),
+                Box::new(Value::Map(vec![(
+                    Value::Str("foo".to_owned()),
+                    Value::I64(42),
+                )])),
+            );
+
+            let (tag, value): (String, Struct<i32>) =
+                deserialize(value, NAME, WithTag::new()).unwrap();
+
+            assert_eq!(tag, "rect");
+            assert_eq!(value, Struct { foo: 42 });
+        }
+
+        #[test]
+        fn native_format_untagged() {
+            let result: Result<i32, _> =
+                deserialize::<String, _, _>(Value::I64(42), NAME, WithoutTag::new());
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn roundtrip() {
+            struct Tagged;
+
+            impl serde::Serialize for Tagged {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    serde_tagged::ser::native::serialize(ser, NAME, &7u8, &[1, 2])
+                }
+            }
+
+            let value = native::to_value(&Tagged).unwrap();
+            let (tag, value): (u8, Vec<i32>) = deserialize(value, NAME, WithTag::new()).unwrap();
+
+            assert_eq!(tag, 7);
+            assert_eq!(value, vec![1, 2]);
+        }
+
+        #[test]
+        fn fallback() {
+            let json = r#"[ "rect", { "foo": "bar" } ]"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let (tag, value): (String, Struct<String>) =
+                deserialize(&mut jde, NAME, WithTag::new()).unwrap();
+
+            assert_eq!(tag, "rect");
+            assert_eq!(
+                value,
+                Struct {
+                    foo: "bar".to_owned(),
+                }
+            );
+        }
+    }
+
+    /// Tests for deserialization of tags depending on human-readability.
+    mod readable {
+        use crate::common::native::Value;
+        use crate::common::types::*;
+        use serde_tagged::de::readable::TagSeed;
+        use serde_tagged::de::WithTag;
+        use serde_tagged::format::TagIds;
+        use serde_tagged::util::TagString;
+
+        const IDS: TagIds = TagIds::new(&[("circle", 1), ("rect", 2)]);
+
+        fn s(v: &str) -> Value {
+            Value::Str(v.to_owned())
+        }
+
+
+        #[test]
+        fn external() {
+            let mut jde = serde_json::Deserializer::from_str(r#"{ "rect": 42 }"#);
+            let (tag, value): (TagString, i32) = serde_tagged::de::external::deserialize_seed(
+                &mut jde,
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+
+            let native = Value::Map(vec![(Value::U64(2), Value::I64(42))]);
+            let (tag, value): (TagString, i32) = serde_tagged::de::external::deserialize_seed(
+                native,
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn adj_tuple() {
+            let native = Value::Seq(vec![Value::U64(1), Value::I64(42)]);
+            let (tag, value): (TagString, i32) = serde_tagged::de::adj::tuple::deserialize_seed(
+                native,
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "circle");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn adj_map() {
+            let native = Value::Map(vec![(s("v"), Value::I64(42)), (s("t"), Value::U64(2))]);
+            let (tag, value): (TagString, i32) =
+                serde_tagged::de::adj::map::deserialize_seed::<String, str, _, _, _>(
+                    native,
+                    "t",
+                    "v",
+                    WithTag::new(),
+                    TagSeed::new(&IDS),
+                )
+                .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn adj_struc() {
+            let native = Value::Map(vec![(s("t"), Value::U64(2)), (s("v"), Value::I64(42))]);
+            let (tag, value): (TagString, i32) = serde_tagged::de::adj::struc::deserialize_seed(
+                native,
+                "Tagged",
+                "t",
+                "v",
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn internal() {
+            let json = r#"{ "foo": "bar", "type": "circle" }"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let (tag, value): (TagString, Struct<String>) =
+                serde_tagged::de::internal::deserialize_seed(
+                    &mut jde,
+                    "type",
+                    WithTag::new(),
+                    TagSeed::new(&IDS),
+                )
+                .unwrap();
+
+            assert_eq!(&*tag, "circle");
+            assert_eq!(value.foo, "bar");
+
+            // internally tagged values are buffered, thus both forms are accepted
+            let native = Value::Map(vec![(s("foo"), s("bar")), (s("type"), Value::U64(1))]);
+            let (tag, value): (TagString, Struct<String>) =
+                serde_tagged::de::internal::deserialize_seed(
+                    native,
+                    "type",
+                    WithTag::new(),
+                    TagSeed::new(&IDS),
+                )
+                .unwrap();
+
+            assert_eq!(&*tag, "circle");
+            assert_eq!(value.foo, "bar");
+        }
+
+        #[test]
+        fn roundtrip() {
+            use crate::common::native;
+            use serde_tagged::ser::readable::Tag;
+
+            struct Tagged;
+
+            impl serde::Serialize for Tagged {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    serde_tagged::ser::adj::tuple::serialize(ser, &Tag::new(&IDS, "rect"), &7)
+                }
+            }
+
+            let value = native::to_value(&Tagged).unwrap();
+            let (tag, value): (TagString, i32) = serde_tagged::de::adj::tuple::deserialize_seed(
+                value,
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 7);
+
+            let json = serde_json::to_string(&Tagged).unwrap();
+            let mut jde = serde_json::Deserializer::from_str(&json);
+            let (tag, value): (TagString, i32) = serde_tagged::de::adj::tuple::deserialize_seed(
+                &mut jde,
+                WithTag::new(),
+                TagSeed::new(&IDS),
+            )
+            .unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 7);
+        }
+
+        #[test]
+        fn unknown() {
+            let native = Value::Seq(vec![Value::U64(3), Value::I64(42)]);
+            let result: Result<(TagString, i32), _> =
+                serde_tagged::de::adj::tuple::deserialize_seed(
+                    native,
+                    WithTag::new(),
+                    TagSeed::new(&IDS),
+                );
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("unknown tag id `3`"));
+
+            let mut jde = serde_json::Deserializer::from_str(r#"[ "square", 42 ]"#);
+            let result: Result<(TagString, i32), _> =
+                serde_tagged::de::adj::tuple::deserialize_seed(
+                    &mut jde,
+                    WithTag::new(),
+                    TagSeed::new(&IDS),
+                );
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("unknown tag `square`"));
+        }
+    }
+
+    /// Tests for deserialization of values tagged with their type name.
+    mod nominal {
+        use serde_tagged::de::dynamic::deserialize;
+        use serde_tagged::de::nominal::name;
+        use serde_tagged::de::WithTag;
+        use serde_tagged::format::TagFormat;
+        use serde_tagged::util::TagString;
+
+        use std::collections::BTreeMap;
+        use std::marker::PhantomData;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename = "point")]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Meters(f64);
+
+
+        #[test]
+        fn names() {
+            assert_eq!(name::<Point>(), Some("point"));
+            assert_eq!(name::<Meters>(), Some("Meters"));
+
+            assert_eq!(name::<i32>(), None);
+            assert_eq!(name::<Vec<i32>>(), None);
+        }
+
+        #[test]
+        fn roundtrip() {
+            let formats = [
+                TagFormat::External,
+                TagFormat::internal("type"),
+                TagFormat::AdjTuple,
+                TagFormat::adj_map("t", "c"),
+                TagFormat::adj_struct("Tagged", "t", "c"),
+            ];
+
+            let point = Point { x: 1, y: 2 };
+
+            for format in &formats {
+                let mut out = Vec::new();
+                let mut ser = serde_json::Serializer::new(&mut out);
+                serde_tagged::ser::nominal::serialize(&mut ser, format, &point).unwrap();
+
+                let mut registry = BTreeMap::new();
+                registry.insert(name::<Point>().unwrap(), PhantomData::<Point>);
+
+                let mut jde = serde_json::Deserializer::from_slice(&out);
+                let value: Point = deserialize(&mut jde, format, registry).unwrap();
+
+                assert_eq!(value, point);
+            }
+        }
+
+        #[test]
+        fn with_tag() {
+            let json = r#"{ "type": "point", "x": 1, "y": 2 }"#;
+            let format = TagFormat::internal("type");
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let (tag, value): (TagString, Point) =
+                deserialize(&mut jde, &format, WithTag::new()).unwrap();
+
+            assert_eq!(Some(&*tag), name::<Point>());
+            assert_eq!(value, Point { x: 1, y: 2 });
+        }
+    }
+
+    /// Tests for deserialization of enum variants.
+    mod variant {
+        use crate::common::types::*;
+        use serde_tagged::de::variant::deserialize;
+        use serde_tagged::de::{WithTag, WithoutTag};
+        use serde_tagged::format::Variants;
+        use serde_tagged::util::TagString;
+
+        const VARIANTS: Variants = Variants::new("Shape", &["circle", "rect"]);
+
+
+        #[test]
+        fn by_name() {
+            let json = r#"{ "rect": { "foo": "bar" } }"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let (tag, value): (TagString, Struct<String>) =
+                deserialize(&mut jde, &VARIANTS, WithTag::new()).unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(
+                value,
+                Struct {
+                    foo: "bar".to_owned(),
+                }
+            );
+        }
+
+        #[test]
+        fn by_index() {
+            use serde_value::Value;
+            use std::collections::BTreeMap;
+
+            let mut map = BTreeMap::new();
+            map.insert(Value::U32(1), Value::I32(42));
+
+            let (tag, value): (TagString, i32) =
+                deserialize(Value::Map(map), &VARIANTS, WithTag::new()).unwrap();
+
+            assert_eq!(&*tag, "rect");
+            assert_eq!(value, 42);
+        }
+
+        #[test]
+        fn roundtrip() {
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+            serde_tagged::ser::variant::serialize(&mut ser, &VARIANTS, "circle", &[1, 2]).unwrap();
+
+            let mut jde = serde_json::Deserializer::from_slice(&out);
+            let value: Vec<i32> = deserialize(&mut jde, &VARIANTS, WithoutTag::new()).unwrap();
+
+            assert_eq!(value, vec![1, 2]);
+        }
+
+        #[test]
+        fn unknown_variant() {
+            let json = r#"{ "square": 1 }"#;
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let result: Result<i32, _> = deserialize(&mut jde, &VARIANTS, WithoutTag::new());
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("unknown variant `square`"));
+
+            let mut map = std::collections::BTreeMap::new();
+            map.insert(serde_value::Value::U32(2), serde_value::Value::I32(42));
+
+            let value = serde_value::Value::Map(map);
+            let result: Result<i32, _> = deserialize(value, &VARIANTS, WithoutTag::new());
+
+            assert!(result.is_err());
+        }
+    }
+
+    /// Tests for deserialization of values with versioned tags.
+    #[cfg(feature = "erased")]
+    mod version {
+        use serde::Deserialize;
+        use serde_tagged::de::version::{Migration, Versioned, VersionedTag};
+        use serde_tagged::de::BoxFnSeed;
+        use serde_tagged::util::Value;
+
+        use std::collections::BTreeMap;
+
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct A {
+            name:  String,
+            count: u64,
+        }
+
+        fn deserialize_a<'de>(
+            de: &mut dyn erased_serde::Deserializer<'de>,
+        ) -> Result<A, erased_serde::Error> {
+            A::deserialize(de)
+        }
+
+        /// Version 1 stored only the name as plain string.
+        fn upgrade_v1(value: Value) -> Result<Value, String> {
+            Ok(Value::Map(vec![(Value::Str("name"), value)]))
+        }
+
+        /// Version 2 did not contain a count.
+        fn upgrade_v2(value: Value) -> Result<Value, String> {
+            match value {
+                Value::Map(mut entries) => {
+                    entries.push((Value::Str("count"), Value::U64(0)));
+                    Ok(Value::Map(entries))
+                },
+                _ => Err("expected a map".to_owned()),
+            }
+        }
+
+        fn registry() -> Versioned<BTreeMap<&'static str, BoxFnSeed<A>>> {
+            let mut seeds = BTreeMap::new();
+            seeds.insert("A", BoxFnSeed::new(deserialize_a));
+
+            let mut registry = Versioned::new(seeds);
+            registry.register(
+                "A",
+                Migration::new(3)
+                    .upgrade(1, upgrade_v1)
+                    .upgrade(2, upgrade_v2),
+            );
+            registry
+        }
+
+
+        #[test]
+        fn tag_parse() {
+            let tag = VersionedTag::parse("A@3".into());
+            assert_eq!(tag, VersionedTag::new("A", 3));
+
+            let tag = VersionedTag::parse("A@3".to_owned().into());
+            assert_eq!(tag, VersionedTag::new("A", 3));
+
+            let tag = VersionedTag::parse("com@example.A".into());
+            assert_eq!(&*tag.name, "com@example.A");
+            assert_eq!(tag.version, None);
+
+            let tag = VersionedTag::parse("a@b@2".into());
+            assert_eq!(tag, VersionedTag::new("a@b", 2));
+
+            let tag = VersionedTag::parse("A".into());
+            assert_eq!(&*tag.name, "A");
+            assert_eq!(tag.version, None);
+
+            let tag = VersionedTag::parse("A@0".into());
+            assert_eq!(tag, VersionedTag::new("A", 0));
+
+            for name in ["A@+1", "A@01", "A@", "A@-1", "A@1 "] {
+                let tag = VersionedTag::parse(name.into());
+                assert_eq!(&*tag.name, name);
+                assert_eq!(tag.version, None);
+            }
+        }
+
+        #[test]
+        fn tag_serialize() {
+            let json = serde_json::to_string(&VersionedTag::new("A", 3)).unwrap();
+            assert_eq!(json, r#""A@3""#);
+        }
+
+        #[test]
+        fn current() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "A@3": { "name": "foo", "count": 42 } }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 42,
+                }
+            );
+        }
+
+        #[test]
+        fn unversioned() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "A": { "name": "foo", "count": 42 } }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 42,
+                }
+            );
+        }
+
+        #[test]
+        fn unversioned_with_separator() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "user@example": { "name": "foo", "count": 42 } }"#;
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert("user@example", BoxFnSeed::new(deserialize_a));
+            let registry = Versioned::new(seeds);
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 42,
+                }
+            );
+        }
+
+        #[test]
+        fn upgrade_chain() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "A@1": "foo" }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn upgrade_adj_tuple() {
+            use serde_tagged::de::adj::tuple::deserialize as de;
+
+            let json = r#"[ "A@2", { "name": "foo" } ]"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn upgrade_internal() {
+            use serde_tagged::de::internal::deserialize as de;
+
+            let json = r#"{ "t": "A@2", "name": "foo" }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let v = de(&mut jde, "t", &registry).unwrap();
+
+            assert_eq!(
+                v,
+                A {
+                    name:  "foo".to_owned(),
+                    count: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn error_version_unsupported() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "A@4": { "name": "foo", "count": 42 } }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let err = de(&mut jde, &registry).unwrap_err();
+
+            assert!(err.to_string().contains("unsupported version 4"));
+        }
+
+        #[test]
+        fn error_upgrade_missing() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "A@1": "foo" }"#;
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert("A", BoxFnSeed::new(deserialize_a));
+
+            let mut registry = Versioned::new(seeds);
+            registry.register("A", Migration::new(3).upgrade(2, upgrade_v2));
+
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let err = de(&mut jde, &registry).unwrap_err();
+
+            assert!(err.to_string().contains("no upgrade from version 1"));
+        }
+
+        #[test]
+        #[should_panic]
+        fn error_unknown_tag() {
+            use serde_tagged::de::external::deserialize as de;
+
+            let json = r#"{ "B@1": "foo" }"#;
+
+            let registry = registry();
+            let mut jde = serde_json::Deserializer::from_str(json);
+            let _v = de(&mut jde, &registry).unwrap();
+        }
+    }
+}
+
+
+/// Tests for descriptions of tagging formats.
+mod format {
+    use serde_tagged::format::TagFormat;
+
+
+    #[test]
+    fn layout() {
+        let formats = [
+            (TagFormat::External, json!("external")),
+            (
+                TagFormat::internal("type"),
+                json!({ "internal": { "tag_key": "type" } }),
+            ),
+            (TagFormat::AdjTuple, json!("adj_tuple")),
+            (
+                TagFormat::adj_map("t", "v"),
+                json!({ "adj_map": { "tag_key": "t", "value_key": "v" } }),
+            ),
+            (
+                TagFormat::adj_struct("Tagged", "t", "v"),
+                json!({ "adj_struct": { "name": "Tagged", "tag_key": "t", "value_key": "v" } }),
+            ),
+        ];
+
+        for (format, json) in formats {
+            assert_eq!(serde_json::to_value(&format).unwrap(), json);
+
+            let de: TagFormat = serde_json::from_value(json).unwrap();
+            assert_eq!(de, format);
+        }
+    }
+
+    #[test]
+    fn sequence() {
+        let de: TagFormat = serde_json::from_str(r#"{ "internal": ["kind"] }"#).unwrap();
+        assert_eq!(de, TagFormat::internal("kind"));
+
+        let de: TagFormat = serde_json::from_str(r#"{ "adj_map": ["t", "v"] }"#).unwrap();
+        assert_eq!(de, TagFormat::adj_map("t", "v"));
+
+        let json = r#"{ "adj_struct": ["Tagged", "t", "v"] }"#;
+        let de: TagFormat = serde_json::from_str(json).unwrap();
+        assert_eq!(de, TagFormat::adj_struct("Tagged", "t", "v"));
+    }
+
+    #[test]
+    fn missing_field() {
+        let json = json!({ "adj_map": { "tag_key": "t" } });
+        let err = serde_json::from_value::<TagFormat>(json).unwrap_err();
+
+        assert!(err.to_string().contains("missing field `value_key`"));
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let err = serde_json::from_value::<TagFormat>(json!("nominal")).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `nominal`"));
+    }
+}
+
+
+/// Tests for schema descriptions of tagged values.
+mod schema {
+    use serde_json::Value;
+    use serde_tagged::format::TagFormat;
+    use serde_tagged::schema::Schema;
+
+
+    fn schema(format: TagFormat) -> Value {
+        let schema = Schema::new().variant("A", format, json!({ "type": "integer" }));
+        serde_json::to_value(&schema).unwrap()
+    }
+
+
+    #[test]
+    fn external() {
+        assert_eq!(
+            schema(TagFormat::External),
+            json!({
+                "oneOf": [{
+                    "type": "object",
+                    "properties": { "A": { "type": "integer" } },
+                    "required": ["A"],
+                    "additionalProperties": false,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn internal() {
+        let payload = json!({
+            "type": "object",
+            "properties": { "n": { "type": "integer" } },
+        });
+
+        let schema = Schema::new().variant("A", TagFormat::internal("t"), payload);
+
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap(),
+            json!({
+                "oneOf": [{
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "properties": { "n": { "type": "integer" } },
+                        },
+                        {
+                            "type": "object",
+                            "properties": { "t": { "const": "A" } },
+                            "required": ["t"],
+                        },
+                    ],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn adj_tuple() {
+        assert_eq!(
+            schema(TagFormat::AdjTuple),
+            json!({
+                "oneOf": [{
+                    "type": "array",
+                    "prefixItems": [{ "const": "A" }, { "type": "integer" }],
+                    "items": false,
+                    "minItems": 2,
+                    "maxItems": 2,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn adj_map() {
+        let expected = json!({
+            "oneOf": [{
+                "type": "object",
+                "properties": {
+                    "t": { "const": "A" },
+                    "c": { "type": "integer" },
+                },
+                "required": ["t", "c"],
+                "additionalProperties": false,
+            }],
+        });
+
+        let format = TagFormat::adj_map("t", "c");
+        assert_eq!(schema(format), expected);
+
+        let format = TagFormat::adj_struct("Tagged", "t", "c");
+        assert_eq!(schema(format), expected);
+    }
+
+    #[test]
+    fn from_registry() {
+        use serde_tagged::de::registry::{Entry, Registry};
+
+        let mut registry = Registry::new();
+        registry.insert("B", Entry::new::<String>(TagFormat::AdjTuple, ()));
+        registry.insert("A", Entry::new::<i64>(TagFormat::External, ()));
+
+        let schema = Schema::from_registry(&registry, |_, entry| match entry.type_name() {
+            "i64" => json!({ "type": "integer" }),
+            _ => json!({ "type": "string" }),
+        });
+
+        let tags: Vec<_> = schema.variants().iter().map(|v| v.tag()).collect();
+        assert_eq!(tags, vec!["A", "B"]);
+
+        let formats: Vec<_> = schema.variants().iter().map(|v| v.format()).collect();
+        assert_eq!(formats, vec![&TagFormat::External, &TagFormat::AdjTuple]);
+
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(
+            json["oneOf"][0]["properties"]["A"],
+            json!({ "type": "integer" })
+        );
+        assert_eq!(
+            json["oneOf"][1]["prefixItems"],
+            json!([{ "const": "B" }, { "type": "string" }])
+        );
+    }
+}
+
+
+/// Tests for the `#[serde(with = "...")]` modules.
+mod tagged {
+    use serde_json::Value;
+    use serde_tagged::format::TagKeys;
+    use serde_tagged::tagged::{AdjMap, AdjStruct, AdjTuple, External, Internal};
+
+    use std::collections::BTreeMap;
+
+
+    struct Keys;
+
+    impl TagKeys for Keys {
+        const TAG_KEY: &'static str = "kind";
+        const VALUE_KEY: &'static str = "data";
+        const NAME: &'static str = "Item";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+
+    #[test]
+    fn external() {
+        let values = vec![
+            External::new("a".to_owned(), json!(1)),
+            External::new("b".to_owned(), json!({ "c": true })),
+        ];
+
+        let json = serde_json::to_value(&values).unwrap();
+        assert_eq!(json, json!([{ "a": 1 }, { "b": { "c": true } }]));
+
+        let de: Vec<External<String, Value>> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, values);
+    }
+
+    #[test]
+    fn internal() {
+        let value: Internal<String, Point> = Internal::new("p".to_owned(), Point { x: 1, y: 2 });
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!({ "type": "p", "x": 1, "y": 2 }));
+
+        let de: Internal<String, Point> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, value);
+
+        let value: Internal<String, Point, Keys> =
+            Internal::new("p".to_owned(), Point { x: 1, y: 2 });
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!({ "kind": "p", "x": 1, "y": 2 }));
+
+        let de: Internal<String, Point, Keys> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, value);
+    }
+
+    #[test]
+    fn adj_tuple() {
+        let value = AdjTuple::new(3u8, "x".to_owned());
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!([3, "x"]));
+
+        let de: AdjTuple<u8, String> = serde_json::from_value(json).unwrap();
+        assert_eq!(de.into_parts(), (3, "x".to_owned()));
+    }
+
+    #[test]
+    fn adj_map() {
+        let mut values = BTreeMap::new();
+        values.insert(
+            "a".to_owned(),
+            AdjMap::new("p".to_owned(), Point { x: 1, y: 2 }),
+        );
+
+        let json = serde_json::to_value(&values).unwrap();
+        assert_eq!(
+            json,
+            json!({ "a": { "type": "p", "value": { "x": 1, "y": 2 } } })
+        );
+
+        let de: BTreeMap<String, AdjMap<String, Point>> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, values);
+
+        let value: AdjMap<String, Point, Keys> = AdjMap::new("p".to_owned(), Point { x: 1, y: 2 });
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!({ "kind": "p", "data": { "x": 1, "y": 2 } }));
+
+        let de: AdjMap<String, Point, Keys> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, value);
+    }
+
+    #[test]
+    fn adj_struct() {
+        let value: AdjStruct<String, Point, Keys> =
+            AdjStruct::new("p".to_owned(), Point { x: 1, y: 2 });
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!({ "kind": "p", "data": { "x": 1, "y": 2 } }));
+
+        let de: AdjStruct<String, Point, Keys> = serde_json::from_value(json).unwrap();
+        assert_eq!(de, value);
+
+        let json = json!({ "type": "p", "value": { "x": 1, "y": 2 } });
+        let de: AdjStruct<String, Point> = serde_json::from_value(json).unwrap();
+        assert_eq!(de.tag, "p");
+        assert_eq!(de.value, Point { x: 1, y: 2 });
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fields {
+        external:   External<String, i32>,
+        internal:   Internal<String, Point>,
+        adj_tuple:  AdjTuple<String, i32>,
+        adj_map:    Vec<AdjMap<String, i32, Keys>>,
+        adj_struct: Option<AdjStruct<String, i32>>,
+    }
+
+    #[test]
+    fn derived() {
+        let fields = Fields {
+            external:   External::new("a".to_owned(), 1),
+            internal:   Internal::new("b".to_owned(), Point { x: 2, y: 3 }),
+            adj_tuple:  AdjTuple::new("c".to_owned(), 4),
+            adj_map:    vec![AdjMap::new("d".to_owned(), 5)],
+            adj_struct: Some(AdjStruct::new("e".to_owned(), 6)),
+        };
+
+        let json = serde_json::to_string(&fields).unwrap();
+        let de: Fields = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(de, fields);
+    }
+
+    #[test]
+    fn generic() {
+        use serde_tagged::format::{self, Format};
+        use serde_tagged::tagged::Tagged;
+
+        fn check<F: Format>(expect: Value) {
+            let value: Tagged<F, _, _> = Tagged::new("p".to_owned(), Point { x: 1, y: 2 });
+
+            let json = serde_json::to_value(&value).unwrap();
+            assert_eq!(json, expect);
+
+            let de: Tagged<F, String, Point> = serde_json::from_value(json).unwrap();
+            assert_eq!(de, value);
+        }
+
+        let point = || Point { x: 1, y: 2 };
+        let tag = || "p".to_owned();
+
+        let expect = serde_json::to_value(External::new(tag(), point())).unwrap();
+        check::<format::External>(expect);
+
+        let expect = serde_json::to_value(AdjTuple::new(tag(), point())).unwrap();
+        check::<format::AdjTuple>(expect);
+
+        let expect = serde_json::to_value(Internal::<_, _>::new(tag(), point())).unwrap();
+        check::<format::Internal>(expect);
+
+        let expect = serde_json::to_value(AdjMap::<_, _, Keys>::new(tag(), point())).unwrap();
+        check::<format::AdjMap<Keys>>(expect);
+
+        let expect = serde_json::to_value(AdjStruct::<_, _, Keys>::new(tag(), point())).unwrap();
+        check::<format::AdjStruct<Keys>>(expect);
+
+        assert_eq!(
+            format::AdjStruct::<Keys>::FORMAT,
+            serde_tagged::format::TagFormat::adj_struct("Item", "kind", "data")
+        );
+    }
+
+    #[test]
+    fn keys() {
+        use serde_tagged::format::{self, Format};
+
+        fn keys<F: Format>() -> [Option<&'static str>; 3] {
+            [F::NAME, F::TAG_KEY, F::VALUE_KEY]
+        }
+
+        assert_eq!(keys::<format::External>(), [None, None, None]);
+        assert_eq!(keys::<format::AdjTuple>(), [None, None, None]);
+        assert_eq!(keys::<format::Internal>(), [None, Some("type"), None]);
+        assert_eq!(
+            keys::<format::AdjMap>(),
+            [None, Some("type"), Some("value")]
+        );
+        assert_eq!(
+            keys::<format::AdjStruct<Keys>>(),
+            [Some("Item"), Some("kind"), Some("data")]
+        );
+    }
+}
+
+
+#[cfg(feature = "erased")]
+mod with {
+    use serde::Deserialize;
+    use serde_tagged::de::BoxFnSeed;
+    use serde_tagged::with::{TagKeys, TagProvider, TagRegistry, Unknown};
+
+    use serde_tagged::util::TagString;
+
+    use std::collections::{BTreeMap, HashMap};
+
+
+    trait Shape: erased_serde::Serialize {
+        fn tag(&self) -> &'static str;
+        fn area(&self) -> f64;
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Rect {
+        w: f64,
+        h: f64,
+    }
+
+    impl Shape for Rect {
+        fn tag(&self) -> &'static str {
+            "rect"
+        }
+
+        fn area(&self) -> f64 {
+            self.w * self.h
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Square {
+        a: f64,
+    }
+
+    impl Shape for Square {
+        fn tag(&self) -> &'static str {
+            "square"
+        }
+
+        fn area(&self) -> f64 {
+            self.a * self.a
+        }
+    }
+
+
+    type Registry = BTreeMap<&'static str, BoxFnSeed<Box<dyn Shape>>>;
+
+    lazy_static::lazy_static! {
+        static ref REGISTRY: Registry = {
+            let mut registry = Registry::new();
+            registry.insert(
+                "rect",
+                BoxFnSeed::new(|de| Ok(Box::new(Rect::deserialize(de)?) as Box<dyn Shape>)),
+            );
+            registry.insert(
+                "square",
+                BoxFnSeed::new(|de| Ok(Box::new(Square::deserialize(de)?) as Box<dyn Shape>)),
+            );
+            registry
+        };
+    }
+
+    impl TagProvider for Box<dyn Shape> {
+        type Tag = str;
+        type Value = dyn erased_serde::Serialize;
+
+        fn tag(&self) -> &str {
+            Shape::tag(&**self)
+        }
+
+        fn value(&self) -> &Self::Value {
+            &**self
+        }
+    }
+
+    impl<'de> TagRegistry<'de> for Box<dyn Shape> {
+        type Tag = TagString<'de>;
+        type Factory = &'static Registry;
+
+        fn registry() -> Self::Factory {
+            &REGISTRY
+        }
+    }
+
+    impl TagKeys for Box<dyn Shape> {
+        const TAG_KEY: &'static str = "kind";
+    }
+
+
+    #[derive(Serialize, Deserialize)]
+    struct Shapes {
+        #[serde(with = "serde_tagged::with::external")]
+        external: Box<dyn Shape>,
+
+        #[serde(with = "serde_tagged::with::internal")]
+        internal: Box<dyn Shape>,
+
+        #[serde(with = "serde_tagged::with::adj_tuple")]
+        adj_tuple: Box<dyn Shape>,
+
+        #[serde(with = "serde_tagged::with::adj_map")]
+        adj_map: Box<dyn Shape>,
+
+        #[serde(with = "serde_tagged::with::adj_struct")]
+        adj_struct: Box<dyn Shape>,
+    }
+
+
+    #[test]
+    fn serialize() {
+        let shapes = Shapes {
+            external:   Box::new(Rect { w: 1.0, h: 2.0 }),
+            internal:   Box::new(Square { a: 3.0 }),
+            adj_tuple:  Box::new(Rect { w: 4.0, h: 5.0 }),
+            adj_map:    Box::new(Square { a: 6.0 }),
+            adj_struct: Box::new(Rect { w: 7.0, h: 8.0 }),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&shapes).unwrap(),
+            json!({
+                "external": { "rect": { "w": 1.0, "h": 2.0 } },
+                "internal": { "kind": "square", "a": 3.0 },
+                "adj_tuple": ["rect", { "w": 4.0, "h": 5.0 }],
+                "adj_map": { "kind": "square", "value": { "a": 6.0 } },
+                "adj_struct": { "kind": "rect", "value": { "w": 7.0, "h": 8.0 } },
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "external": { "rect": { "w": 1.0, "h": 2.0 } },
+            "internal": { "kind": "square", "a": 3.0 },
+            "adj_tuple": ["rect", { "w": 4.0, "h": 5.0 }],
+            "adj_map": { "kind": "square", "value": { "a": 6.0 } },
+            "adj_struct": { "kind": "rect", "value": { "w": 7.0, "h": 8.0 } }
+        }"#;
+
+        let shapes: Shapes = serde_json::from_str(json).unwrap();
+
+        assert_eq!(shapes.external.area(), 2.0);
+        assert_eq!(shapes.internal.area(), 9.0);
+        assert_eq!(shapes.adj_tuple.area(), 20.0);
+        assert_eq!(shapes.adj_map.area(), 36.0);
+        assert_eq!(shapes.adj_struct.area(), 56.0);
+    }
+
+    #[test]
+    fn deserialize_error_unknown_tag() {
+        let json = r#"{
+            "external": { "circle": { "r": 1.0 } },
+            "internal": { "kind": "square", "a": 3.0 },
+            "adj_tuple": ["rect", { "w": 4.0, "h": 5.0 }],
+            "adj_map": { "kind": "square", "value": { "a": 6.0 } },
+            "adj_struct": { "kind": "rect", "value": { "w": 7.0, "h": 8.0 } }
+        }"#;
+
+        assert!(serde_json::from_str::<Shapes>(json).is_err());
+    }
+
+
+    #[derive(Serialize, Deserialize)]
+    struct Collections {
+        #[serde(with = "serde_tagged::with::external::seq")]
+        seq: Vec<Box<dyn Shape>>,
+
+        #[serde(with = "serde_tagged::with::adj_tuple::map")]
+        map: BTreeMap<String, Box<dyn Shape>>,
+
+        #[serde(with = "serde_tagged::with::internal::map")]
+        hash_map: HashMap<String, Box<dyn Shape>>,
+
+        #[serde(with = "serde_tagged::with::adj_map::option")]
+        some: Option<Box<dyn Shape>>,
+
+        #[serde(with = "serde_tagged::with::adj_struct::option")]
+        none: Option<Box<dyn Shape>>,
+    }
+
+    #[test]
+    fn serialize_collections() {
+        let mut map: BTreeMap<String, Box<dyn Shape>> = BTreeMap::new();
+        map.insert("a".to_owned(), Box::new(Square { a: 1.0 }));
+
+        let mut hash_map: HashMap<String, Box<dyn Shape>> = HashMap::new();
+        hash_map.insert("b".to_owned(), Box::new(Rect { w: 1.0, h: 2.0 }));
+
+        let collections = Collections {
+            seq: vec![
+                Box::new(Rect { w: 1.0, h: 2.0 }),
+                Box::new(Square { a: 3.0 }),
+            ],
+            map,
+            hash_map,
+            some: Some(Box::new(Square { a: 4.0 })),
+            none: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&collections).unwrap(),
+            json!({
+                "seq": [
+                    { "rect": { "w": 1.0, "h": 2.0 } },
+                    { "square": { "a": 3.0 } },
+                ],
+                "map": { "a": ["square", { "a": 1.0 }] },
+                "hash_map": { "b": { "kind": "rect", "w": 1.0, "h": 2.0 } },
+                "some": { "kind": "square", "value": { "a": 4.0 } },
+                "none": null,
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_collections() {
+        let json = r#"{
+            "seq": [
+                { "rect": { "w": 1.0, "h": 2.0 } },
+                { "square": { "a": 3.0 } }
+            ],
+            "map": { "a": ["square", { "a": 1.0 }] },
+            "hash_map": { "b": { "kind": "rect", "w": 1.0, "h": 2.0 } },
+            "some": { "kind": "square", "value": { "a": 4.0 } },
+            "none": null
+        }"#;
+
+        let collections: Collections = serde_json::from_str(json).unwrap();
+
+        let areas: Vec<_> = collections.seq.iter().map(|s| s.area()).collect();
+        assert_eq!(areas, vec![2.0, 9.0]);
+        assert_eq!(collections.map["a"].area(), 1.0);
+        assert_eq!(collections.hash_map["b"].area(), 2.0);
+        assert_eq!(collections.some.map(|s| s.area()), Some(16.0));
+        assert!(collections.none.is_none());
+    }
+
+    #[test]
+    fn deserialize_collections_error_unknown_tag() {
+        let json = r#"[{ "rect": { "w": 1.0, "h": 2.0 } }, { "circle": { "r": 1.0 } }]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let result: Result<Vec<Box<dyn Shape>>, _> =
+            serde_tagged::with::external::seq::deserialize(&mut de);
+
+        assert!(result.is_err());
+    }
+
+
+    struct Lenient(Box<dyn Shape>);
+
+    type LenientRegistry = BTreeMap<&'static str, BoxFnSeed<Lenient>>;
+
+    lazy_static::lazy_static! {
+        static ref LENIENT: LenientRegistry = {
+            let mut registry = BTreeMap::new();
+            registry.insert(
+                "rect",
+                BoxFnSeed::new(|de| Ok(Lenient(Box::new(Rect::deserialize(de)?)))),
+            );
+            registry.insert(
+                "square",
+                BoxFnSeed::new(|de| Ok(Lenient(Box::new(Square::deserialize(de)?)))),
+            );
+            registry
+        };
+    }
+
+    impl<'de> TagRegistry<'de> for Lenient {
+        type Tag = TagString<'de>;
+        type Factory = &'static LenientRegistry;
+
+        const UNKNOWN: Unknown = Unknown::Skip;
+
+        fn registry() -> Self::Factory {
+            &LENIENT
+        }
+    }
+
+    impl TagKeys for Lenient {}
+
+    #[test]
+    fn deserialize_collections_skip_unknown() {
+        let json = r#"[
+            { "type": "rect", "value": { "w": 1.0, "h": 2.0 } },
+            { "type": "circle", "value": { "r": 1.0 } },
+            { "type": "square", "value": { "a": 3.0 } }
+        ]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let shapes: Vec<Lenient> = serde_tagged::with::adj_map::seq::deserialize(&mut de).unwrap();
+
+        let areas: Vec<_> = shapes.iter().map(|s| s.0.area()).collect();
+        assert_eq!(areas, vec![2.0, 9.0]);
+
+        let json = r#"{ "a": ["circle", { "r": 1.0 }], "b": ["square", { "a": 2.0 }] }"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let shapes: BTreeMap<String, Lenient> =
+            serde_tagged::with::adj_tuple::map::deserialize(&mut de).unwrap();
+
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes["b"].0.area(), 4.0);
+
+        let json = r#"{ "type": "circle", "r": 1.0 }"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let shape: Option<Lenient> =
+            serde_tagged::with::internal::option::deserialize(&mut de).unwrap();
+
+        assert!(shape.is_none());
+    }
+
+    struct Migrated(Box<dyn Shape>);
+
+    type MigratedRegistry =
+        serde_tagged::de::version::Versioned<BTreeMap<&'static str, BoxFnSeed<Migrated>>>;
+
+    lazy_static::lazy_static! {
+        static ref MIGRATED: MigratedRegistry = {
+            use serde_tagged::de::version::{Migration, Versioned};
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert(
+                "rect",
+                BoxFnSeed::new(|de| Ok(Migrated(Box::new(Rect::deserialize(de)?)))),
+            );
+
+            let mut registry = Versioned::new(seeds);
+            registry.register("rect", Migration::new(1));
+            registry
+        };
+    }
+
+    impl<'de> TagRegistry<'de> for Migrated {
+        type Tag = TagString<'de>;
+        type Factory = &'static MigratedRegistry;
+
+        const UNKNOWN: Unknown = Unknown::Skip;
+
+        fn registry() -> Self::Factory {
+            &MIGRATED
+        }
+    }
+
+    #[test]
+    fn deserialize_collections_skip_only_unknown() {
+        let json = r#"[{ "rect@1": { "w": 1.0, "h": 2.0 } }, { "circle": { "r": 1.0 } }]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let shapes: Vec<Migrated> =
+            serde_tagged::with::external::seq::deserialize(&mut de).unwrap();
+
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].0.area(), 2.0);
+
+        let json = r#"[{ "rect@2": { "w": 1.0, "h": 2.0 } }, { "circle": { "r": 1.0 } }]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let result: Result<Vec<Migrated>, _> =
+            serde_tagged::with::external::seq::deserialize(&mut de);
+
+        let err = result.err().unwrap();
+        assert!(err.to_string().contains("unsupported version 2 of `rect`"));
+    }
+
+    struct Vendored(Box<dyn Shape>);
+
+    type VendoredRegistry =
+        serde_tagged::de::registry::Namespaced<BTreeMap<&'static str, BoxFnSeed<Vendored>>>;
+
+    lazy_static::lazy_static! {
+        static ref VENDORED: VendoredRegistry = {
+            use serde_tagged::de::registry::Namespaced;
+
+            let mut seeds = BTreeMap::new();
+            seeds.insert(
+                "rect",
+                BoxFnSeed::new(|de| Ok(Vendored(Box::new(Rect::deserialize(de)?)))),
+            );
+
+            let mut registry = Namespaced::new('.');
+            registry.insert("vendor", seeds);
+            registry
+        };
+    }
+
+    impl<'de> TagRegistry<'de> for Vendored {
+        type Tag = TagString<'de>;
+        type Factory = &'static VendoredRegistry;
+
+        const UNKNOWN: Unknown = Unknown::Skip;
+
+        fn registry() -> Self::Factory {
+            &VENDORED
+        }
+    }
+
+    #[test]
+    fn deserialize_collections_skip_unknown_namespace() {
+        let json = r#"[
+            { "vendor.rect": { "w": 1.0, "h": 2.0 } },
+            { "other.rect": { "w": 1.0, "h": 2.0 } },
+            { "rect": { "w": 1.0, "h": 2.0 } },
+            { "vendor.circle": { "r": 1.0 } }
+        ]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let shapes: Vec<Vendored> =
+            serde_tagged::with::external::seq::deserialize(&mut de).unwrap();
+
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].0.area(), 2.0);
+    }
+
+    #[test]
+    fn deserialize_collections_collect_unknown() {
+        use serde_tagged::util::Value;
+
+        let json = r#"[
+            { "rect": { "w": 1.0, "h": 2.0 } },
+            { "circle": { "r": 1.0 } }
+        ]"#;
+
+        let mut de = serde_json::Deserializer::from_str(json);
+        let (shapes, unknown): (Vec<Box<dyn Shape>>, _) =
+            serde_tagged::with::external::seq::deserialize_collect(&mut de).unwrap();
+
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].tag, TagString::from("circle"));
+        assert_eq!(
+            unknown[0].value,
+            Value::Map(vec![(Value::Str("r"), Value::F64(1.0))])
+        );
+    }
+
+
+    #[cfg(feature = "std")]
+    mod shared {
+        use super::{Lenient, LenientRegistry, Rect, Shape, Square, LENIENT};
+
+        use serde_tagged::util::TagString;
+        use serde_tagged::with::share;
+        use serde_tagged::with::{TagKeys, TagProvider, TagRegistry, Unknown};
+
+        use std::rc::Rc;
+
+
+        impl TagProvider for dyn Shape {
+            type Tag = str;
+            type Value = dyn erased_serde::Serialize;
+
+            fn tag(&self) -> &str {
+                Shape::tag(self)
+            }
+
+            fn value(&self) -> &Self::Value {
+                self
+            }
+        }
+
+        impl TagKeys for dyn Shape {}
+
+
+        #[derive(Serialize, Deserialize)]
+        struct Drawing {
+            #[serde(with = "serde_tagged::with::external::shared")]
+            main: Rc<dyn Shape>,
+
+            #[serde(with = "serde_tagged::with::adj_map::shared::seq")]
+            shapes: Vec<Rc<dyn Shape>>,
+        }
+
+
+        #[test]
+        fn serialize() {
+            let rect: Rc<dyn Shape> = Rc::new(Rect { w: 1.0, h: 2.0 });
+            let square: Rc<dyn Shape> = Rc::new(Square { a: 3.0 });
+
+            let drawing = Drawing {
+                main:   rect.clone(),
+                shapes: vec![square.clone(), rect, square],
+            };
+
+            let value = share::scope(|| serde_json::to_value(&drawing).unwrap());
+
+            assert_eq!(
+                value,
+                json!({
+                    "main": { "$id": 0, "$value": { "rect": { "w": 1.0, "h": 2.0 } } },
+                    "shapes": [
+                        { "$id": 1, "$value": { "type": "square", "value": { "a": 3.0 } } },
+                        { "$ref": 0 },
+                        { "$ref": 1 },
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn deserialize() {
+            let json = r#"{
+                "main": { "$id": 0, "$value": { "rect": { "w": 1.0, "h": 2.0 } } },
+                "shapes": [
+                    { "$id": 1, "$value": { "type": "square", "value": { "a": 3.0 } } },
+                    { "$ref": 0 },
+                    { "$ref": 1 }
+                ]
+            }"#;
+
+            let drawing: Drawing = share::scope(|| serde_json::from_str(json).unwrap());
+
+            assert_eq!(drawing.main.area(), 2.0);
+            assert_eq!(drawing.shapes[0].area(), 9.0);
+            assert!(Rc::ptr_eq(&drawing.main, &drawing.shapes[1]));
+            assert!(Rc::ptr_eq(&drawing.shapes[0], &drawing.shapes[2]));
+            assert!(!Rc::ptr_eq(&drawing.main, &drawing.shapes[0]));
+        }
+
+        #[test]
+        fn roundtrip_scopes() {
+            let rect: Rc<dyn Shape> = Rc::new(Rect { w: 1.0, h: 2.0 });
+
+            let drawing = Drawing {
+                main:   rect.clone(),
+                shapes: vec![rect],
+            };
+
+            let json = share::scope(|| serde_json::to_string(&drawing).unwrap());
+            let json = share::scope(|| {
+                // ids of the outer scope are independent of the inner one
+                let inner = share::scope(|| serde_json::to_string(&drawing).unwrap());
+                assert_eq!(
+                    serde_json::from_str::<serde_json::Value>(&inner).unwrap()["main"]["$id"],
+                    0
+                );
+
+                serde_json::from_str::<Drawing>(&json).unwrap();
+                json
+            });
+
+            let drawing: Drawing = share::scope(|| serde_json::from_str(&json).unwrap());
+            assert!(Rc::ptr_eq(&drawing.main, &drawing.shapes[0]));
+        }
+
+        #[test]
+        fn error_outside_scope() {
+            let drawing = Drawing {
+                main:   Rc::new(Square { a: 1.0 }),
+                shapes: vec![],
+            };
+
+            assert!(serde_json::to_string(&drawing).is_err());
+
+            let json = r#"{
+                "main": { "$id": 0, "$value": { "square": { "a": 1.0 } } },
+                "shapes": []
+            }"#;
+
+            assert!(serde_json::from_str::<Drawing>(json).is_err());
+        }
+
+        #[test]
+        fn error_unknown_reference() {
+            let json = r#"{
+                "main": { "$ref": 0 },
+                "shapes": []
+            }"#;
+
+            assert!(share::scope(|| serde_json::from_str::<Drawing>(json)).is_err());
+
+            let json = r#"{
+                "main": { "$id": 0, "$value": { "square": { "a": 1.0 } } },
+                "shapes": [{ "$ref": 1 }]
+            }"#;
+
+            assert!(share::scope(|| serde_json::from_str::<Drawing>(json)).is_err());
+        }
+
+        /// A shape that fails to serialize.
+        struct Broken;
+
+        impl serde::Serialize for Broken {
+            fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("broken"))
+            }
+        }
+
+        impl Shape for Broken {
+            fn tag(&self) -> &'static str {
+                "broken"
+            }
+
+            fn area(&self) -> f64 {
+                0.0
+            }
+        }
+
+        #[test]
+        fn serialize_error_assigns_no_id() {
+            let broken: Rc<dyn Shape> = Rc::new(Broken);
+            let rect: Rc<dyn Shape> = Rc::new(Rect { w: 1.0, h: 2.0 });
+
+            share::scope(|| {
+                let drawing = Drawing {
+                    main:   broken.clone(),
+                    shapes: vec![],
+                };
+
+                // a failed value must not be referenced later on
+                assert!(serde_json::to_value(&drawing).is_err());
+                assert!(serde_json::to_value(&drawing).is_err());
+
+                let drawing = Drawing {
+                    main:   rect.clone(),
+                    shapes: vec![rect.clone()],
+                };
+
+                let value = serde_json::to_value(&drawing).unwrap();
+                assert_eq!(value["shapes"][0]["$ref"], value["main"]["$id"]);
+            });
+        }
+
+
+        impl<'de> TagRegistry<'de> for Box<Lenient> {
+            type Tag = TagString<'de>;
+            type Factory = share::Convert<&'static LenientRegistry, Box<Lenient>>;
+
+            const UNKNOWN: Unknown = Unknown::Skip;
+
+            fn registry() -> Self::Factory {
+                share::Convert::new(&LENIENT)
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct LenientDrawing {
+            #[serde(with = "serde_tagged::with::external::shared::seq")]
+            shapes: Vec<Rc<Lenient>>,
+        }
+
+        const LENIENT_JSON: &str = r#"{
+            "shapes": [
+                { "$id": 0, "$value": { "circle": { "r": 1.0 } } },
+                { "$id": 1, "$value": { "square": { "a": 3.0 } } },
+                { "$ref": 0 },
+                { "$ref": 1 }
+            ]
+        }"#;
+
+        #[test]
+        fn deserialize_skip_unknown_references() {
+            let drawing: LenientDrawing =
+                share::scope(|| serde_json::from_str(LENIENT_JSON).unwrap());
+
+            assert_eq!(drawing.shapes.len(), 2);
+            assert_eq!(drawing.shapes[0].0.area(), 9.0);
+            assert!(Rc::ptr_eq(&drawing.shapes[0], &drawing.shapes[1]));
+        }
+
+        #[test]
+        fn deserialize_collect_unknown_references() {
+            use serde_tagged::with::external::shared::seq::deserialize_collect;
+
+            let value: serde_json::Value = serde_json::from_str(LENIENT_JSON).unwrap();
+
+            let (shapes, unknown): (Vec<Rc<Lenient>>, _) =
+                share::scope(|| deserialize_collect(&value["shapes"]).unwrap());
+
+            // the reference to the skipped value is not reported again
+            assert_eq!(shapes.len(), 2);
+            assert_eq!(unknown.len(), 1);
+            assert_eq!(unknown[0].tag, TagString::from("circle"));
+        }
+    }
 }
@@ -0,0 +1,67 @@
+//! Tests using `rmp-serde`, a data format supporting tags natively.
+
+extern crate rmp_serde;
+extern crate serde;
+extern crate serde_bytes;
+extern crate serde_tagged;
+
+
+/// Tests for values tagged as MessagePack extension types.
+mod native {
+    use serde_bytes::{ByteBuf, Bytes};
+    use serde_tagged::de::WithTag;
+    use serde_tagged::ser::native::{MSGPACK_EXT, NAME};
+
+
+    struct Tagged<'a> {
+        name:  &'static str,
+        tag:   i8,
+        value: &'a [u8],
+    }
+
+    impl<'a> serde::Serialize for Tagged<'a> {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            serde_tagged::ser::native::serialize(ser, self.name, &self.tag, Bytes::new(self.value))
+        }
+    }
+
+    fn deserialize(name: &'static str, bytes: &[u8]) -> (i8, ByteBuf) {
+        let mut de = rmp_serde::Deserializer::from_read_ref(bytes);
+        serde_tagged::de::native::deserialize(&mut de, name, WithTag::new()).unwrap()
+    }
+
+
+    #[test]
+    fn extension_type() {
+        let value = Tagged {
+            name:  MSGPACK_EXT,
+            tag:   5,
+            value: &[1, 2, 3, 4],
+        };
+
+        // fixext 4, followed by the type and data
+        let bytes = rmp_serde::to_vec(&value).unwrap();
+        assert_eq!(bytes, [0xd6, 5, 1, 2, 3, 4]);
+
+        let (tag, value) = deserialize(MSGPACK_EXT, &bytes);
+        assert_eq!(tag, 5);
+        assert_eq!(value.as_slice(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fallback() {
+        let value = Tagged {
+            name:  NAME,
+            tag:   5,
+            value: &[1, 2],
+        };
+
+        // array of length 2, followed by the tag and bin 8 data
+        let bytes = rmp_serde::to_vec(&value).unwrap();
+        assert_eq!(bytes, [0x92, 5, 0xc4, 2, 1, 2]);
+
+        let (tag, value) = deserialize(NAME, &bytes);
+        assert_eq!(tag, 5);
+        assert_eq!(value.as_slice(), [1, 2]);
+    }
+}